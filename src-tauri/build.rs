@@ -1,3 +1,156 @@
+#[path = "src/wav.rs"]
+mod wav;
+
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::{
+  env, fs,
+  path::{Path, PathBuf},
+};
+
+const WEB_DIR: &str = "../web";
+/// JS lists in `web/index.html` whose file names must exist.
+const CHECKED_LISTS: [&str; 3] = [
+  "const expressions = [",
+  "const dragVoices = [",
+  "const satiatedClickVoices = [",
+];
+
 fn main() {
+  write_asset_manifest();
   tauri_build::build()
 }
+
+fn png_dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
+  if bytes.len() < 24 || &bytes[..8] != b"\x89PNG\r\n\x1a\n" || &bytes[12..16] != b"IHDR" {
+    return None;
+  }
+  let width = u32::from_be_bytes(bytes[16..20].try_into().ok()?);
+  let height = u32::from_be_bytes(bytes[20..24].try_into().ok()?);
+  Some((width, height))
+}
+
+fn describe(folder: &str, path: &Path) -> Value {
+  let bytes = fs::read(path).unwrap_or_else(|error| panic!("cannot read {}: {error}", path.display()));
+  let name = path.file_name().unwrap_or_default().to_string_lossy();
+  let sha256: String = Sha256::digest(&bytes)
+    .iter()
+    .map(|byte| format!("{byte:02x}"))
+    .collect();
+  let mut entry = json!({
+    "folder": folder,
+    "name": name,
+    "bytes": bytes.len(),
+    "sha256": sha256,
+  });
+
+  let is = |extension: &str| {
+    path
+      .extension()
+      .is_some_and(|actual| actual.eq_ignore_ascii_case(extension))
+  };
+  if is("png") {
+    let (width, height) = png_dimensions(&bytes).unwrap_or_else(|| panic!("{} is not a PNG", path.display()));
+    entry["width"] = json!(width);
+    entry["height"] = json!(height);
+  } else if is("wav") {
+    let info = wav::parse_wav(&bytes).unwrap_or_else(|error| panic!("{}: {error}", path.display()));
+    entry["sample_rate"] = json!(info.sample_rate);
+    entry["channels"] = json!(info.channels);
+    entry["bits_per_sample"] = json!(info.bits_per_sample);
+    entry["duration_ms"] = json!((info.duration_secs * 1000.0).round() as u64);
+  }
+  entry
+}
+
+fn list_folder(folder: &str) -> Vec<PathBuf> {
+  let dir = Path::new(WEB_DIR).join(folder);
+  println!("cargo:rerun-if-changed={}", dir.display());
+  let mut paths: Vec<PathBuf> = fs::read_dir(&dir)
+    .unwrap_or_else(|error| panic!("cannot read {}: {error}", dir.display()))
+    .filter_map(|entry| entry.ok())
+    .map(|entry| entry.path())
+    .filter(|path| path.is_file())
+    .collect();
+  paths.sort();
+  paths
+}
+
+/// The double-quoted string literals in `source`.
+fn string_literals(source: &str) -> Vec<String> {
+  source
+    .split('"')
+    .skip(1)
+    .step_by(2)
+    .map(|literal| literal.to_string())
+    .collect()
+}
+
+/// File names the frontend, the bundled character pack and the bundled food
+/// catalog refer to, as `(where, folder, name)`.
+fn referenced_files() -> Vec<(String, &'static str, String)> {
+  let mut sources = Vec::new();
+
+  let index_path = Path::new(WEB_DIR).join("index.html");
+  println!("cargo:rerun-if-changed={}", index_path.display());
+  let index = fs::read_to_string(&index_path).expect("cannot read web/index.html");
+  for declaration in CHECKED_LISTS {
+    let start = index
+      .find(declaration)
+      .unwrap_or_else(|| panic!("web/index.html no longer declares `{declaration}`"));
+    let list = &index[start..];
+    let end = list.find("];").unwrap_or(list.len());
+    let name = declaration.trim_start_matches("const ").trim_end_matches(" = [");
+    sources.push((format!("web/index.html `{name}`"), list[..end].to_string()));
+  }
+
+  for config in ["character.json", "foods.json"] {
+    println!("cargo:rerun-if-changed={config}");
+    let text = fs::read_to_string(config).unwrap_or_else(|error| panic!("cannot read {config}: {error}"));
+    sources.push((config.to_string(), text));
+  }
+
+  let mut files = Vec::new();
+  for (source, text) in sources {
+    for literal in string_literals(&text) {
+      let lower = literal.to_ascii_lowercase();
+      let folder = if lower.ends_with(".png") {
+        "images"
+      } else if lower.ends_with(".wav") {
+        "voice"
+      } else {
+        continue;
+      };
+      files.push((source.clone(), folder, literal));
+    }
+  }
+  files
+}
+
+/// Describes everything in `web/images` and `web/voice` into
+/// `$OUT_DIR/assets.json` for `list_assets`, and stops the build when the
+/// frontend names a file that is not there.
+fn write_asset_manifest() {
+  let mut entries = Vec::new();
+  let mut present = Vec::new();
+  for folder in ["images", "voice"] {
+    for path in list_folder(folder) {
+      let name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+      entries.push(describe(folder, &path));
+      present.push((folder, name));
+    }
+  }
+
+  let missing: Vec<String> = referenced_files()
+    .into_iter()
+    .filter(|(_, folder, name)| !present.iter().any(|(known, file)| known == folder && file == name))
+    .map(|(source, folder, name)| format!("  {source} refers to web/{folder}/{name}, which does not exist"))
+    .collect();
+  if !missing.is_empty() {
+    panic!("missing assets:\n{}", missing.join("\n"));
+  }
+
+  let out_dir = PathBuf::from(env::var("OUT_DIR").expect("OUT_DIR is set by cargo"));
+  let manifest = serde_json::to_string(&entries).expect("asset manifest serializes");
+  fs::write(out_dir.join("assets.json"), manifest).expect("cannot write assets.json");
+}
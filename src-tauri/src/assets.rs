@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+/// Written by `build.rs` from `web/images` and `web/voice`.
+const ASSET_MANIFEST: &str = include_str!(concat!(env!("OUT_DIR"), "/assets.json"));
+
+static ASSETS: OnceLock<Vec<AssetEntry>> = OnceLock::new();
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AssetEntry {
+  /// `images` or `voice`.
+  pub folder: String,
+  pub name: String,
+  pub bytes: u64,
+  pub sha256: String,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub width: Option<u32>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub height: Option<u32>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub sample_rate: Option<u32>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub channels: Option<u16>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub bits_per_sample: Option<u16>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub duration_ms: Option<u64>,
+}
+
+/// Every bundled image and voice clip, sorted by folder and name.
+pub fn bundled_assets() -> &'static [AssetEntry] {
+  ASSETS.get_or_init(|| serde_json::from_str(ASSET_MANIFEST).expect("build.rs writes a valid asset manifest"))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn manifest_lists_the_bundled_files_in_order() {
+    let assets = bundled_assets();
+    assert!(assets
+      .iter()
+      .any(|asset| asset.folder == "images" && asset.name == "hobak.png" && asset.width.is_some()));
+    assert!(assets
+      .iter()
+      .any(|asset| asset.folder == "voice" && asset.duration_ms.is_some()));
+    assert!(assets
+      .windows(2)
+      .all(|pair| (&pair[0].folder, &pair[0].name) <= (&pair[1].folder, &pair[1].name)));
+  }
+}
@@ -0,0 +1,412 @@
+//! Native voice playback. Every clip plays on a channel; a channel holds one
+//! clip at a time, and a new clip only cuts in when its category has at least
+//! the priority of the one already playing.
+
+use crate::wav;
+use serde::{Deserialize, Serialize};
+use std::{
+  collections::HashMap,
+  io::Cursor,
+  sync::Arc,
+  time::{Duration, Instant},
+};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AudioCategory {
+  /// Chatter: clicks, greetings, idle emotes.
+  #[default]
+  Speech,
+  /// Protests and pain; cuts through anything.
+  Reaction,
+  /// Game cues, such as hide-and-seek calls.
+  Game,
+  /// Voices requested by user scripts; never interrupt the character.
+  Script,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum AudioChannel {
+  /// The character's own voice.
+  Voice,
+  /// Game cues, which may overlap the character.
+  Game,
+}
+
+impl AudioCategory {
+  fn channel(self) -> AudioChannel {
+    match self {
+      AudioCategory::Game => AudioChannel::Game,
+      AudioCategory::Speech | AudioCategory::Reaction | AudioCategory::Script => AudioChannel::Voice,
+    }
+  }
+
+  fn priority(self) -> u8 {
+    match self {
+      AudioCategory::Reaction => 3,
+      AudioCategory::Speech | AudioCategory::Game => 2,
+      AudioCategory::Script => 1,
+    }
+  }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CategoryVolumes {
+  pub speech: f32,
+  pub reaction: f32,
+  pub game: f32,
+  pub script: f32,
+}
+
+impl Default for CategoryVolumes {
+  fn default() -> Self {
+    Self {
+      speech: 1.0,
+      reaction: 1.0,
+      game: 1.0,
+      script: 1.0,
+    }
+  }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioSettings {
+  pub muted: bool,
+  pub master_volume: f32,
+  pub volumes: CategoryVolumes,
+}
+
+impl Default for AudioSettings {
+  fn default() -> Self {
+    Self {
+      muted: false,
+      master_volume: 1.0,
+      volumes: CategoryVolumes::default(),
+    }
+  }
+}
+
+impl AudioSettings {
+  /// Master times category volume, 0 when muted.
+  pub fn volume(&self, category: AudioCategory) -> f32 {
+    if self.muted {
+      return 0.0;
+    }
+    let volumes = &self.volumes;
+    let category_volume = match category {
+      AudioCategory::Speech => volumes.speech,
+      AudioCategory::Reaction => volumes.reaction,
+      AudioCategory::Game => volumes.game,
+      AudioCategory::Script => volumes.script,
+    };
+    (self.master_volume * category_volume).clamp(0.0, 1.0)
+  }
+}
+
+#[derive(Clone, Debug)]
+pub struct VoiceRequest {
+  pub clip: String,
+  pub bytes: Arc<[u8]>,
+  pub category: AudioCategory,
+  /// -1 is hard left, 1 hard right.
+  pub pan: f32,
+  /// The character pack's own voice volume.
+  pub gain: f32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlayOutcome {
+  Started,
+  /// Started after cutting off a clip of equal or lower priority.
+  Interrupted,
+  /// Something more important is playing on the same channel.
+  Dropped,
+  Muted,
+}
+
+pub trait Playback {
+  fn stop(&mut self);
+  fn is_finished(&self) -> bool;
+}
+
+pub trait AudioBackend {
+  fn play(&mut self, bytes: Arc<[u8]>, volume: f32, pan: f32) -> Result<Box<dyn Playback>, String>;
+}
+
+/// Plays nothing, but keeps each clip "busy" for as long as it would last.
+/// Used when no output device is available, and by tests.
+#[derive(Debug, Default)]
+pub struct NullBackend;
+
+struct NullPlayback {
+  until: Instant,
+}
+
+impl Playback for NullPlayback {
+  fn stop(&mut self) {
+    self.until = Instant::now();
+  }
+
+  fn is_finished(&self) -> bool {
+    Instant::now() >= self.until
+  }
+}
+
+impl AudioBackend for NullBackend {
+  fn play(&mut self, bytes: Arc<[u8]>, _volume: f32, _pan: f32) -> Result<Box<dyn Playback>, String> {
+    let info = wav::parse_wav(&bytes)?;
+    Ok(Box::new(NullPlayback {
+      until: Instant::now() + Duration::from_secs_f64(info.duration_secs),
+    }))
+  }
+}
+
+/// The default output device. Not `Send`: create it on the thread that plays.
+pub struct RodioBackend {
+  _stream: rodio::OutputStream,
+  handle: rodio::OutputStreamHandle,
+}
+
+impl RodioBackend {
+  pub fn open() -> Result<Self, String> {
+    let (stream, handle) = rodio::OutputStream::try_default().map_err(|error| error.to_string())?;
+    Ok(Self {
+      _stream: stream,
+      handle,
+    })
+  }
+}
+
+struct RodioPlayback(rodio::Sink);
+
+impl Playback for RodioPlayback {
+  fn stop(&mut self) {
+    self.0.stop();
+  }
+
+  fn is_finished(&self) -> bool {
+    self.0.empty()
+  }
+}
+
+impl AudioBackend for RodioBackend {
+  fn play(&mut self, bytes: Arc<[u8]>, volume: f32, pan: f32) -> Result<Box<dyn Playback>, String> {
+    let decoder = rodio::Decoder::new(Cursor::new(bytes.to_vec())).map_err(|error| error.to_string())?;
+    let pan = pan.clamp(-1.0, 1.0);
+    let panned = rodio::source::ChannelVolume::new(decoder, vec![(1.0 - pan).min(1.0), (1.0 + pan).min(1.0)]);
+    let sink = rodio::Sink::try_new(&self.handle).map_err(|error| error.to_string())?;
+    sink.set_volume(volume);
+    sink.append(panned);
+    Ok(Box::new(RodioPlayback(sink)))
+  }
+}
+
+struct Playing {
+  category: AudioCategory,
+  clip: String,
+  playback: Box<dyn Playback>,
+}
+
+pub struct Mixer {
+  backend: Box<dyn AudioBackend>,
+  channels: HashMap<AudioChannel, Playing>,
+}
+
+impl Mixer {
+  pub fn new(backend: Box<dyn AudioBackend>) -> Self {
+    Self {
+      backend,
+      channels: HashMap::new(),
+    }
+  }
+
+  /// Forgets clips that have finished playing.
+  pub fn reap(&mut self) {
+    self.channels.retain(|_, playing| !playing.playback.is_finished());
+  }
+
+  pub fn play(&mut self, settings: &AudioSettings, request: VoiceRequest) -> Result<PlayOutcome, String> {
+    self.reap();
+    let volume = settings.volume(request.category) * request.gain.clamp(0.0, 1.0);
+    if settings.muted || volume <= 0.0 {
+      return Ok(PlayOutcome::Muted);
+    }
+
+    let channel = request.category.channel();
+    let interrupted = match self.channels.remove(&channel) {
+      Some(current) if current.category.priority() > request.category.priority() => {
+        log::info!("dropped voice {} over {}", request.clip, current.clip);
+        self.channels.insert(channel, current);
+        return Ok(PlayOutcome::Dropped);
+      }
+      Some(mut current) => {
+        current.playback.stop();
+        true
+      }
+      None => false,
+    };
+
+    let playback = self.backend.play(request.bytes, volume, request.pan)?;
+    self.channels.insert(
+      channel,
+      Playing {
+        category: request.category,
+        clip: request.clip,
+        playback,
+      },
+    );
+    Ok(if interrupted {
+      PlayOutcome::Interrupted
+    } else {
+      PlayOutcome::Started
+    })
+  }
+
+  pub fn stop_all(&mut self) {
+    for (_, mut playing) in self.channels.drain() {
+      playing.playback.stop();
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// Ten seconds of 8 kHz mono 8-bit silence, long enough to still be
+  /// playing when the next request arrives.
+  fn long_clip() -> Arc<[u8]> {
+    let data_len: u32 = 80_000;
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(b"RIFF");
+    bytes.extend_from_slice(&(36 + data_len).to_le_bytes());
+    bytes.extend_from_slice(b"WAVEfmt ");
+    for field in [
+      16u32.to_le_bytes(),
+      [1, 0, 1, 0],
+      8_000u32.to_le_bytes(),
+      8_000u32.to_le_bytes(),
+      [1, 0, 8, 0],
+    ] {
+      bytes.extend_from_slice(&field);
+    }
+    bytes.extend_from_slice(b"data");
+    bytes.extend_from_slice(&data_len.to_le_bytes());
+    bytes.resize(bytes.len() + data_len as usize, 128);
+    bytes.into()
+  }
+
+  fn request(clip: &str, category: AudioCategory) -> VoiceRequest {
+    VoiceRequest {
+      clip: clip.into(),
+      bytes: long_clip(),
+      category,
+      pan: 0.0,
+      gain: 1.0,
+    }
+  }
+
+  fn mixer() -> Mixer {
+    Mixer::new(Box::new(NullBackend))
+  }
+
+  #[test]
+  fn lower_priority_is_dropped_while_a_clip_plays() {
+    let settings = AudioSettings::default();
+    let mut mixer = mixer();
+    let reaction = mixer.play(&settings, request("ouch", AudioCategory::Reaction));
+    assert_eq!(reaction, Ok(PlayOutcome::Started));
+
+    let speech = mixer.play(&settings, request("hello", AudioCategory::Speech));
+    assert_eq!(speech, Ok(PlayOutcome::Dropped));
+    let script = mixer.play(&settings, request("script", AudioCategory::Script));
+    assert_eq!(script, Ok(PlayOutcome::Dropped));
+  }
+
+  #[test]
+  fn equal_or_higher_priority_interrupts() {
+    let settings = AudioSettings::default();
+    let mut mixer = mixer();
+    mixer.play(&settings, request("hello", AudioCategory::Speech)).unwrap();
+
+    let again = mixer.play(&settings, request("hi", AudioCategory::Speech));
+    assert_eq!(again, Ok(PlayOutcome::Interrupted));
+    let reaction = mixer.play(&settings, request("ouch", AudioCategory::Reaction));
+    assert_eq!(reaction, Ok(PlayOutcome::Interrupted));
+  }
+
+  #[test]
+  fn game_cues_have_their_own_channel() {
+    let settings = AudioSettings::default();
+    let mut mixer = mixer();
+    mixer.play(&settings, request("ouch", AudioCategory::Reaction)).unwrap();
+
+    let cue = mixer.play(&settings, request("ready", AudioCategory::Game));
+    assert_eq!(cue, Ok(PlayOutcome::Started));
+    let next_cue = mixer.play(&settings, request("found", AudioCategory::Game));
+    assert_eq!(next_cue, Ok(PlayOutcome::Interrupted));
+  }
+
+  #[test]
+  fn stopped_channels_are_free_again() {
+    let settings = AudioSettings::default();
+    let mut mixer = mixer();
+    mixer.play(&settings, request("ouch", AudioCategory::Reaction)).unwrap();
+    mixer.stop_all();
+
+    let speech = mixer.play(&settings, request("hello", AudioCategory::Speech));
+    assert_eq!(speech, Ok(PlayOutcome::Started));
+  }
+
+  #[test]
+  fn muted_or_silent_volumes_play_nothing() {
+    let mut mixer = mixer();
+    let muted = AudioSettings {
+      muted: true,
+      ..AudioSettings::default()
+    };
+    assert_eq!(
+      mixer.play(&muted, request("hello", AudioCategory::Speech)),
+      Ok(PlayOutcome::Muted)
+    );
+
+    let silent_master = AudioSettings {
+      master_volume: 0.0,
+      ..AudioSettings::default()
+    };
+    assert_eq!(
+      mixer.play(&silent_master, request("hello", AudioCategory::Speech)),
+      Ok(PlayOutcome::Muted)
+    );
+
+    let silent_speech = AudioSettings {
+      volumes: CategoryVolumes {
+        speech: 0.0,
+        ..CategoryVolumes::default()
+      },
+      ..AudioSettings::default()
+    };
+    assert_eq!(
+      mixer.play(&silent_speech, request("hello", AudioCategory::Speech)),
+      Ok(PlayOutcome::Muted)
+    );
+
+    let silent_pack = VoiceRequest {
+      gain: 0.0,
+      ..request("hello", AudioCategory::Speech)
+    };
+    assert_eq!(
+      mixer.play(&AudioSettings::default(), silent_pack),
+      Ok(PlayOutcome::Muted)
+    );
+
+    let settings = AudioSettings::default();
+    assert_eq!(
+      mixer.play(&settings, request("hello", AudioCategory::Speech)),
+      Ok(PlayOutcome::Started)
+    );
+  }
+}
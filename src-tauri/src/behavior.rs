@@ -0,0 +1,472 @@
+use crate::{
+  clock::{in_daily_window, parse_clock_time},
+  mood::Mood,
+  rng::Dice,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+  fs,
+  path::Path,
+  sync::{Mutex, OnceLock},
+};
+
+const TREE_FILE_NAME: &str = "behaviors.json";
+const DEFAULT_TREE: &str = include_str!("../behaviors.json");
+
+static BEHAVIOR_TREE: OnceLock<Mutex<BehaviorTree>> = OnceLock::new();
+
+/// What the character knows about itself when it decides what to do next.
+#[derive(Clone, Debug)]
+pub struct Blackboard {
+  pub pumpkin_index: u32,
+  pub energy: u32,
+  pub fun: u32,
+  pub mood: Mood,
+  pub satisfied: bool,
+  pub asleep: bool,
+  pub sick: bool,
+  pub food_present: bool,
+  pub minute_of_day: u32,
+  /// Seconds since the user last clicked, dragged or fed the character.
+  pub idle_secs: u64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "check", rename_all = "snake_case")]
+pub enum Condition {
+  Asleep,
+  Sick,
+  Satisfied,
+  FoodPresent,
+  PumpkinIndexBelow {
+    value: u32,
+  },
+  EnergyBelow {
+    value: u32,
+  },
+  FunBelow {
+    value: u32,
+  },
+  MoodIs {
+    moods: Vec<Mood>,
+  },
+  /// Local time in `[start, end)`, both `HH:MM`.
+  Hours {
+    start: String,
+    end: String,
+  },
+  IdleLongerThan {
+    secs: u64,
+  },
+  Chance {
+    probability: f64,
+  },
+  Not {
+    condition: Box<Condition>,
+  },
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WeightedNode {
+  pub weight: u32,
+  pub node: Node,
+}
+
+/// A behavior tree node. Composites pick or chain their children; the other
+/// variants are activities the frontend knows how to play.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Node {
+  /// Runs the first child that succeeds.
+  Selector {
+    children: Vec<Node>,
+  },
+  /// Runs every child in order; fails as soon as one fails.
+  Sequence {
+    children: Vec<Node>,
+  },
+  /// Runs one child picked by weight.
+  Random {
+    children: Vec<WeightedNode>,
+  },
+  /// Succeeds without doing anything when the check holds.
+  Condition {
+    when: Condition,
+  },
+  Wander {
+    #[serde(default = "default_wander_min_px")]
+    min_px: u32,
+    /// `None` lets the character cross most of the screen.
+    #[serde(default)]
+    max_px: Option<u32>,
+  },
+  Sit {
+    secs: f64,
+  },
+  LookAround {
+    #[serde(default = "default_look_turns")]
+    turns: u32,
+  },
+  Sleep {
+    secs: u64,
+  },
+  SeekPumpkin,
+  Emote {
+    /// Expression id; `None` picks one of the happy idle expressions.
+    #[serde(default)]
+    expression: Option<String>,
+    #[serde(default)]
+    voice: Option<String>,
+    /// How long to hold the expression; `None` keeps it until something else changes it.
+    #[serde(default)]
+    secs: Option<f64>,
+  },
+}
+
+fn default_wander_min_px() -> u32 {
+  90
+}
+
+fn default_look_turns() -> u32 {
+  2
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct PauseRange {
+  pub min_ms: u64,
+  pub max_ms: u64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BehaviorTree {
+  /// Rest between two plans.
+  pub pause: PauseRange,
+  pub root: Node,
+}
+
+/// The activities chosen for this turn, played in order.
+#[derive(Clone, Debug, Serialize)]
+pub struct Plan {
+  pub activities: Vec<Node>,
+  pub pause_ms: u64,
+}
+
+impl Condition {
+  fn holds(&self, board: &Blackboard, dice: &mut Dice) -> bool {
+    match self {
+      Condition::Asleep => board.asleep,
+      Condition::Sick => board.sick,
+      Condition::Satisfied => board.satisfied,
+      Condition::FoodPresent => board.food_present,
+      Condition::PumpkinIndexBelow { value } => board.pumpkin_index < *value,
+      Condition::EnergyBelow { value } => board.energy < *value,
+      Condition::FunBelow { value } => board.fun < *value,
+      Condition::MoodIs { moods } => moods.contains(&board.mood),
+      Condition::Hours { start, end } => match (parse_clock_time(start), parse_clock_time(end)) {
+        (Some(start), Some(end)) => in_daily_window(start, end, board.minute_of_day),
+        _ => false,
+      },
+      Condition::IdleLongerThan { secs } => board.idle_secs > *secs,
+      Condition::Chance { probability } => dice.next_f64() < *probability,
+      Condition::Not { condition } => !condition.holds(board, dice),
+    }
+  }
+
+  fn validate(&self) -> Result<(), String> {
+    match self {
+      Condition::Hours { start, end } if parse_clock_time(start).is_none() || parse_clock_time(end).is_none() => {
+        Err(format!("hours `{start}`-`{end}` must be HH:MM"))
+      }
+      Condition::Chance { probability } if !(0.0..=1.0).contains(probability) => {
+        Err(format!("chance {probability} is outside 0..=1"))
+      }
+      Condition::Not { condition } => condition.validate(),
+      _ => Ok(()),
+    }
+  }
+}
+
+impl Node {
+  /// Appends the activities this node chose to `plan`. Returns `false` when
+  /// the node failed; a failed sequence may leave partial work in `plan`,
+  /// which the caller discards.
+  fn run(&self, board: &Blackboard, dice: &mut Dice, plan: &mut Vec<Node>) -> bool {
+    match self {
+      Node::Selector { children } => children.iter().any(|child| {
+        let mark = plan.len();
+        let succeeded = child.run(board, dice, plan);
+        if !succeeded {
+          plan.truncate(mark);
+        }
+        succeeded
+      }),
+      Node::Sequence { children } => children.iter().all(|child| child.run(board, dice, plan)),
+      Node::Random { children } => {
+        let total: u64 = children.iter().map(|child| child.weight as u64).sum();
+        if total == 0 {
+          return false;
+        }
+        let mut roll = dice.next_u64() % total;
+        for child in children {
+          let weight = child.weight as u64;
+          if roll < weight {
+            return child.node.run(board, dice, plan);
+          }
+          roll -= weight;
+        }
+        false
+      }
+      Node::Condition { when } => when.holds(board, dice),
+      activity => {
+        plan.push(activity.clone());
+        true
+      }
+    }
+  }
+
+  fn validate(&self) -> Result<(), String> {
+    match self {
+      Node::Selector { children } | Node::Sequence { children } => {
+        if children.is_empty() {
+          return Err("selector and sequence nodes need children".into());
+        }
+        children.iter().try_for_each(Node::validate)
+      }
+      Node::Random { children } => {
+        if children.iter().all(|child| child.weight == 0) {
+          return Err("random node needs a child with a positive weight".into());
+        }
+        children.iter().try_for_each(|child| child.node.validate())
+      }
+      Node::Condition { when } => when.validate(),
+      Node::Wander { min_px, max_px } => match max_px {
+        Some(max_px) if max_px < min_px => Err(format!("wander max_px {max_px} is below min_px {min_px}")),
+        _ => Ok(()),
+      },
+      Node::Sit { secs } => {
+        if !secs.is_finite() || *secs < 0.0 {
+          return Err("sit secs must be a positive number".into());
+        }
+        Ok(())
+      }
+      Node::Emote { expression, voice, .. } => {
+        let is_path = |name: &Option<String>| {
+          name
+            .as_deref()
+            .is_some_and(|name| name.contains("..") || name.contains('/') || name.contains('\\'))
+        };
+        if is_path(expression) || is_path(voice) {
+          return Err("emote expression and voice must be plain names".into());
+        }
+        Ok(())
+      }
+      Node::LookAround { .. } | Node::Sleep { .. } | Node::SeekPumpkin => Ok(()),
+    }
+  }
+}
+
+impl BehaviorTree {
+  fn validate(&self) -> Result<(), String> {
+    if self.pause.max_ms < self.pause.min_ms {
+      return Err("pause max_ms is below min_ms".into());
+    }
+    self.root.validate()
+  }
+
+  /// Walks the tree once. An empty plan means nothing applied this turn.
+  pub fn plan(&self, board: &Blackboard, seed: u64) -> Plan {
+    let mut dice = Dice::new(seed);
+    let mut activities = Vec::new();
+    if !self.root.run(board, &mut dice, &mut activities) {
+      activities.clear();
+    }
+    if board.sick {
+      // A sick character stays put whatever the tree says.
+      activities.retain(|activity| !matches!(activity, Node::Wander { .. }));
+    }
+    let span = self.pause.max_ms - self.pause.min_ms;
+    let pause_ms = self.pause.min_ms + dice.next_u64() % (span + 1);
+    Plan { activities, pause_ms }
+  }
+}
+
+fn default_tree() -> BehaviorTree {
+  serde_json::from_str(DEFAULT_TREE).expect("bundled behaviors.json must be valid")
+}
+
+fn behavior_tree_state() -> &'static Mutex<BehaviorTree> {
+  BEHAVIOR_TREE.get_or_init(|| Mutex::new(default_tree()))
+}
+
+/// Loads `behaviors.json` from `config_dir` if present, keeping the bundled
+/// tree when the override is missing or invalid.
+pub fn load_tree(config_dir: &Path) {
+  let path = config_dir.join(TREE_FILE_NAME);
+  let Ok(raw) = fs::read_to_string(&path) else {
+    return;
+  };
+
+  let parsed = serde_json::from_str::<BehaviorTree>(&raw)
+    .map_err(|error| error.to_string())
+    .and_then(|tree| tree.validate().map(|_| tree));
+  match parsed {
+    Ok(tree) => {
+      if let Ok(mut current) = behavior_tree_state().lock() {
+        *current = tree;
+      }
+    }
+    Err(error) => log::warn!("ignoring {}: {}", path.display(), error),
+  }
+}
+
+pub fn current_tree() -> BehaviorTree {
+  behavior_tree_state()
+    .lock()
+    .map(|tree| tree.clone())
+    .unwrap_or_else(|_| default_tree())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use serde_json::json;
+
+  fn board() -> Blackboard {
+    Blackboard {
+      pumpkin_index: 80,
+      energy: 80,
+      fun: 80,
+      mood: Mood::Content,
+      satisfied: true,
+      asleep: false,
+      sick: false,
+      food_present: false,
+      minute_of_day: 12 * 60,
+      idle_secs: 0,
+    }
+  }
+
+  fn tree(root: serde_json::Value) -> BehaviorTree {
+    serde_json::from_value(json!({ "pause": { "min_ms": 100, "max_ms": 200 }, "root": root })).unwrap()
+  }
+
+  fn kinds(plan: &Plan) -> Vec<&'static str> {
+    plan
+      .activities
+      .iter()
+      .map(|activity| match activity {
+        Node::Wander { .. } => "wander",
+        Node::Sit { .. } => "sit",
+        Node::LookAround { .. } => "look_around",
+        Node::Sleep { .. } => "sleep",
+        Node::SeekPumpkin => "seek_pumpkin",
+        Node::Emote { .. } => "emote",
+        _ => "composite",
+      })
+      .collect()
+  }
+
+  #[test]
+  fn selector_discards_a_failed_childs_partial_plan() {
+    let tree = tree(json!({ "type": "selector", "children": [
+      { "type": "sequence", "children": [
+        { "type": "emote" },
+        { "type": "condition", "when": { "check": "asleep" } }
+      ] },
+      { "type": "sit", "secs": 3 }
+    ] }));
+    assert_eq!(kinds(&tree.plan(&board(), 1)), ["sit"]);
+  }
+
+  #[test]
+  fn a_failed_root_plans_nothing() {
+    let tree = tree(json!({ "type": "sequence", "children": [
+      { "type": "wander" },
+      { "type": "condition", "when": { "check": "fun_below", "value": 50 } }
+    ] }));
+    assert_eq!(kinds(&tree.plan(&board(), 1)), Vec::<&str>::new());
+
+    let bored = Blackboard { fun: 20, ..board() };
+    assert_eq!(kinds(&tree.plan(&bored, 1)), ["wander"]);
+  }
+
+  #[test]
+  fn random_never_picks_a_zero_weight_child() {
+    let tree = tree(json!({ "type": "random", "children": [
+      { "weight": 0, "node": { "type": "wander" } },
+      { "weight": 3, "node": { "type": "sit", "secs": 1 } }
+    ] }));
+    for seed in 0..200 {
+      let plan = tree.plan(&board(), seed);
+      assert_eq!(kinds(&plan), ["sit"]);
+      assert!((100..=200).contains(&plan.pause_ms));
+    }
+  }
+
+  #[test]
+  fn sick_characters_do_not_wander() {
+    let tree = tree(json!({ "type": "sequence", "children": [
+      { "type": "wander" },
+      { "type": "look_around" }
+    ] }));
+    let sick = Blackboard { sick: true, ..board() };
+    assert_eq!(kinds(&tree.plan(&sick, 1)), ["look_around"]);
+  }
+
+  #[test]
+  fn hours_wrap_past_midnight() {
+    let night = Condition::Hours {
+      start: "22:00".into(),
+      end: "06:00".into(),
+    };
+    let mut dice = Dice::new(1);
+    let at = |minute_of_day| Blackboard {
+      minute_of_day,
+      ..board()
+    };
+    assert!(night.holds(&at(23 * 60), &mut dice));
+    assert!(night.holds(&at(5 * 60), &mut dice));
+    assert!(!night.holds(&at(12 * 60), &mut dice));
+  }
+
+  #[test]
+  fn validation_rejects_broken_trees() {
+    let broken = [
+      json!({ "type": "selector", "children": [] }),
+      json!({ "type": "random", "children": [{ "weight": 0, "node": { "type": "wander" } }] }),
+      json!({ "type": "condition", "when": { "check": "hours", "start": "25:00", "end": "06:00" } }),
+      json!({ "type": "condition", "when": { "check": "chance", "probability": 1.5 } }),
+      json!({ "type": "wander", "min_px": 200, "max_px": 100 }),
+      json!({ "type": "sit", "secs": -1.0 }),
+      json!({ "type": "emote", "voice": "../secret.wav" }),
+    ];
+    for root in broken {
+      assert!(tree(root.clone()).validate().is_err(), "{root} should be rejected");
+    }
+
+    let mut backwards = tree(json!({ "type": "wander" }));
+    backwards.pause = PauseRange {
+      min_ms: 300,
+      max_ms: 100,
+    };
+    assert!(backwards.validate().is_err());
+  }
+
+  #[test]
+  fn bundled_tree_is_valid() {
+    let tree = default_tree();
+    assert!(tree.validate().is_ok());
+
+    let asleep = Blackboard {
+      asleep: true,
+      ..board()
+    };
+    assert_eq!(kinds(&tree.plan(&asleep, 1)), ["sit"]);
+    let hungry = Blackboard {
+      food_present: true,
+      pumpkin_index: 40,
+      ..board()
+    };
+    assert_eq!(kinds(&tree.plan(&hungry, 1)), ["seek_pumpkin"]);
+  }
+}
@@ -0,0 +1,319 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// What a bond level adds on top of the previous ones.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BondLevel {
+  pub name: String,
+  pub min_points: f64,
+  /// Expression ids added to the idle expression shuffle.
+  pub expressions: Vec<String>,
+  /// Voice files added to the click voices.
+  pub voices: Vec<String>,
+  /// Behavior names the frontend knows how to play.
+  pub behaviors: Vec<String>,
+}
+
+fn level(name: &str, min_points: f64) -> BondLevel {
+  BondLevel {
+    name: name.into(),
+    min_points,
+    ..BondLevel::default()
+  }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BondSettings {
+  pub points_per_meal: f64,
+  pub points_per_pet: f64,
+  /// Petting stops counting once it has earned this much in a day.
+  pub daily_pet_points_cap: f64,
+  /// Bonus on the first care of a day, times the streak length.
+  pub streak_bonus_per_day: f64,
+  pub max_streak_bonus_days: u32,
+  /// After this long without care the bond starts to fade.
+  pub neglect_grace_hours: u64,
+  pub neglect_points_per_day: f64,
+  /// Sorted by `min_points` when the settings are loaded.
+  pub levels: Vec<BondLevel>,
+}
+
+impl Default for BondSettings {
+  fn default() -> Self {
+    Self {
+      points_per_meal: 2.0,
+      points_per_pet: 0.2,
+      daily_pet_points_cap: 4.0,
+      streak_bonus_per_day: 1.0,
+      max_streak_bonus_days: 7,
+      neglect_grace_hours: 24,
+      neglect_points_per_day: 3.0,
+      levels: vec![
+        level("Stranger", 0.0),
+        BondLevel {
+          voices: vec!["I like pumpkin.WAV".into()],
+          ..level("Friend", 20.0)
+        },
+        BondLevel {
+          expressions: vec!["blush".into()],
+          ..level("Close friend", 60.0)
+        },
+        BondLevel {
+          behaviors: vec!["greet".into()],
+          ..level("Best friend", 150.0)
+        },
+      ],
+    }
+  }
+}
+
+impl BondSettings {
+  /// Level lookups count thresholds in order, so a hand-edited
+  /// `settings.json` listing them out of order is put right here.
+  pub fn sort_levels(&mut self) {
+    self
+      .levels
+      .sort_by(|left, right| left.min_points.total_cmp(&right.min_points));
+  }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Care {
+  Meal,
+  Pet,
+}
+
+/// Persisted bond progress.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BondRecord {
+  pub points: f64,
+  /// Days in a row with at least one meal or pet.
+  pub streak_days: u32,
+  /// `YYYY-MM-DD HH:MM:SS` of the last meal or pet.
+  pub last_care: Option<String>,
+  pub pet_points_today: f64,
+  /// `YYYY-MM-DD HH:MM:SS` up to which neglect has already been taken off.
+  pub neglected_until: Option<String>,
+}
+
+/// Current level plus everything unlocked so far, for the frontend.
+#[derive(Clone, Debug, Serialize)]
+pub struct BondStatus {
+  pub level: u32,
+  pub name: String,
+  pub points: u32,
+  pub next_level_at: Option<u32>,
+  pub expressions: Vec<String>,
+  pub voices: Vec<String>,
+  pub behaviors: Vec<String>,
+}
+
+impl BondRecord {
+  fn last_care_at(&self) -> Option<NaiveDateTime> {
+    let last_care = self.last_care.as_deref()?;
+    NaiveDateTime::parse_from_str(last_care, TIMESTAMP_FORMAT).ok()
+  }
+
+  fn neglected_until_at(&self) -> Option<NaiveDateTime> {
+    let neglected_until = self.neglected_until.as_deref()?;
+    NaiveDateTime::parse_from_str(neglected_until, TIMESTAMP_FORMAT).ok()
+  }
+
+  pub fn level(&self, settings: &BondSettings) -> u32 {
+    settings
+      .levels
+      .iter()
+      .filter(|level| self.points >= level.min_points)
+      .count()
+      .saturating_sub(1) as u32
+  }
+
+  /// Adds the points for one act of care. Returns the new level when this
+  /// pushed the bond past a threshold.
+  pub fn care(&mut self, settings: &BondSettings, now: NaiveDateTime, care: Care) -> Option<u32> {
+    let before = self.level(settings);
+    let today = now.date();
+    let last_day: Option<NaiveDate> = self.last_care_at().map(|at| at.date());
+
+    if last_day != Some(today) {
+      self.streak_days = match last_day {
+        Some(day) if today.signed_duration_since(day).num_days() == 1 => self.streak_days + 1,
+        _ => 1,
+      };
+      self.pet_points_today = 0.0;
+      self.points += settings.streak_bonus_per_day * self.streak_days.min(settings.max_streak_bonus_days) as f64;
+    }
+    self.last_care = Some(now.format(TIMESTAMP_FORMAT).to_string());
+
+    match care {
+      Care::Meal => self.points += settings.points_per_meal,
+      Care::Pet => {
+        let earned = settings
+          .points_per_pet
+          .min((settings.daily_pet_points_cap - self.pet_points_today).max(0.0));
+        self.pet_points_today += earned;
+        self.points += earned;
+      }
+    }
+
+    let after = self.level(settings);
+    (after > before).then_some(after)
+  }
+
+  /// Fades the bond by `elapsed_ms` worth of neglect once the grace period
+  /// since the last care has passed.
+  pub fn tick(&mut self, settings: &BondSettings, now: NaiveDateTime, elapsed_ms: u64) {
+    let Some(last_care) = self.last_care_at() else {
+      return;
+    };
+    let neglected_secs = now.signed_duration_since(last_care).num_seconds();
+    if neglected_secs < settings.neglect_grace_hours.saturating_mul(3600) as i64 {
+      return;
+    }
+    self.fade(settings, now, elapsed_ms);
+  }
+
+  /// Takes off the neglect accrued while the app was closed, with the same
+  /// grace period and rate as `tick`. Called once after the save is loaded.
+  pub fn catch_up(&mut self, settings: &BondSettings, now: NaiveDateTime) {
+    let Some(last_care) = self.last_care_at() else {
+      return;
+    };
+    let grace_secs = settings.neglect_grace_hours.saturating_mul(3600) as i64;
+    let applied_secs = self
+      .neglected_until_at()
+      .map_or(0, |until| until.signed_duration_since(last_care).num_seconds());
+    let owed_secs = now.signed_duration_since(last_care).num_seconds() - grace_secs.max(applied_secs);
+    if owed_secs > 0 {
+      self.fade(settings, now, owed_secs as u64 * 1000);
+    }
+  }
+
+  fn fade(&mut self, settings: &BondSettings, now: NaiveDateTime, elapsed_ms: u64) {
+    let days = elapsed_ms as f64 / 86_400_000.0;
+    self.points = (self.points - settings.neglect_points_per_day * days).max(0.0);
+    self.neglected_until = Some(now.format(TIMESTAMP_FORMAT).to_string());
+  }
+
+  pub fn status(&self, settings: &BondSettings) -> BondStatus {
+    let level = self.level(settings);
+    let unlocked = settings.levels.iter().take(level as usize + 1);
+    BondStatus {
+      level,
+      name: settings
+        .levels
+        .get(level as usize)
+        .map(|level| level.name.clone())
+        .unwrap_or_default(),
+      points: self.points.floor() as u32,
+      next_level_at: settings
+        .levels
+        .get(level as usize + 1)
+        .map(|next| next.min_points.ceil() as u32),
+      expressions: unlocked.clone().flat_map(|level| level.expressions.clone()).collect(),
+      voices: unlocked.clone().flat_map(|level| level.voices.clone()).collect(),
+      behaviors: unlocked.flat_map(|level| level.behaviors.clone()).collect(),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn at(day: u32, hour: u32) -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2026, 3, day)
+      .and_then(|date| date.and_hms_opt(hour, 0, 0))
+      .unwrap()
+  }
+
+  fn cared_for_at(time: NaiveDateTime) -> BondRecord {
+    BondRecord {
+      points: 30.0,
+      last_care: Some(time.format(TIMESTAMP_FORMAT).to_string()),
+      ..BondRecord::default()
+    }
+  }
+
+  #[test]
+  fn catch_up_skips_the_grace_period() {
+    let settings = BondSettings::default();
+    let mut bond = cared_for_at(at(1, 12));
+
+    bond.catch_up(&settings, at(2, 11));
+    assert_eq!(bond.points, 30.0);
+
+    bond.catch_up(&settings, at(4, 12));
+    assert_eq!(bond.points, 24.0);
+  }
+
+  #[test]
+  fn catch_up_does_not_repeat_neglect_already_ticked() {
+    let settings = BondSettings::default();
+    let mut bond = cared_for_at(at(1, 12));
+
+    bond.tick(&settings, at(3, 12), 86_400_000);
+    assert_eq!(bond.points, 27.0);
+
+    bond.catch_up(&settings, at(4, 12));
+    assert_eq!(bond.points, 24.0);
+  }
+
+  #[test]
+  fn first_care_of_the_day_adds_the_streak_bonus() {
+    let settings = BondSettings::default();
+    let mut bond = BondRecord::default();
+
+    bond.care(&settings, at(1, 9), Care::Meal);
+    bond.care(&settings, at(1, 18), Care::Meal);
+    assert_eq!((bond.points, bond.streak_days), (5.0, 1));
+
+    bond.care(&settings, at(2, 9), Care::Meal);
+    assert_eq!((bond.points, bond.streak_days), (9.0, 2));
+
+    bond.care(&settings, at(4, 9), Care::Meal);
+    assert_eq!((bond.points, bond.streak_days), (12.0, 1));
+  }
+
+  #[test]
+  fn petting_stops_counting_at_the_daily_cap() {
+    let settings = BondSettings::default();
+    let mut bond = BondRecord::default();
+
+    for _ in 0..40 {
+      bond.care(&settings, at(1, 9), Care::Pet);
+    }
+    assert!((bond.points - 5.0).abs() < 1e-9);
+
+    bond.care(&settings, at(2, 9), Care::Pet);
+    assert!((bond.pet_points_today - settings.points_per_pet).abs() < 1e-9);
+  }
+
+  #[test]
+  fn care_reports_only_the_level_it_crossed_into() {
+    let settings = BondSettings::default();
+    let mut bond = BondRecord {
+      points: 19.0,
+      ..cared_for_at(at(1, 9))
+    };
+
+    assert_eq!(bond.care(&settings, at(1, 10), Care::Meal), Some(1));
+    assert_eq!(bond.care(&settings, at(1, 11), Care::Meal), None);
+    assert_eq!(bond.status(&settings).next_level_at, Some(60));
+  }
+
+  #[test]
+  fn levels_are_sorted_by_threshold() {
+    let mut settings = BondSettings::default();
+    settings.levels.reverse();
+    settings.sort_levels();
+
+    let names: Vec<&str> = settings.levels.iter().map(|level| level.name.as_str()).collect();
+    assert_eq!(names, ["Stranger", "Friend", "Close friend", "Best friend"]);
+  }
+}
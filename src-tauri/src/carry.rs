@@ -0,0 +1,109 @@
+use crate::{food::MoodEffect, path_planner::Rect};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct StashPoint {
+  pub x: i32,
+  pub y: i32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CarrySettings {
+  pub enabled: bool,
+  /// A reached pumpkin is carried to the stash instead of eaten when the
+  /// pumpkin index is at or above this.
+  pub hoard_at_or_above: u32,
+  pub stash_capacity: u32,
+  /// Where the carried food's center sits relative to the character's center.
+  pub hold_offset_x: i32,
+  pub hold_offset_y: i32,
+  /// Top-left corner the character walks to; `None` uses the bottom-left
+  /// corner of the current monitor.
+  pub stash: Option<StashPoint>,
+}
+
+impl Default for CarrySettings {
+  fn default() -> Self {
+    Self {
+      enabled: true,
+      hoard_at_or_above: 80,
+      stash_capacity: 12,
+      hold_offset_x: 70,
+      hold_offset_y: 30,
+      stash: None,
+    }
+  }
+}
+
+impl CarrySettings {
+  pub fn should_carry(&self, pumpkin_index: u32, mood_effect: MoodEffect, stashed: usize) -> bool {
+    self.enabled
+      && mood_effect != MoodEffect::Disliked
+      && pumpkin_index >= self.hoard_at_or_above
+      && (stashed as u32) < self.stash_capacity
+  }
+
+  /// Offset from the character's top-left corner to the carried window's.
+  pub fn hold_offset(&self, main_w: i32, main_h: i32, item_w: i32, item_h: i32) -> (i32, i32) {
+    (
+      (main_w - item_w) / 2 + self.hold_offset_x,
+      (main_h - item_h) / 2 + self.hold_offset_y,
+    )
+  }
+
+  /// Where the character window should end up to drop food at the stash.
+  pub fn stash_target(&self, bounds: Rect, main_w: i32, main_h: i32) -> (i32, i32) {
+    let (x, y) = match self.stash {
+      Some(point) => (point.x, point.y),
+      None => (bounds.x, bounds.bottom() - main_h),
+    };
+    (
+      x.clamp(bounds.x, (bounds.right() - main_w).max(bounds.x)),
+      y.clamp(bounds.y, (bounds.bottom() - main_h).max(bounds.y)),
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn carries_only_liked_food_when_full_and_the_stash_has_room() {
+    let settings = CarrySettings::default();
+    assert!(settings.should_carry(80, MoodEffect::Happy, 0));
+    assert!(!settings.should_carry(79, MoodEffect::Happy, 0));
+    assert!(!settings.should_carry(100, MoodEffect::Disliked, 0));
+    assert!(!settings.should_carry(100, MoodEffect::Happy, 12));
+
+    let disabled = CarrySettings {
+      enabled: false,
+      ..CarrySettings::default()
+    };
+    assert!(!disabled.should_carry(100, MoodEffect::Happy, 0));
+  }
+
+  #[test]
+  fn holds_the_item_centered_plus_the_offset() {
+    let settings = CarrySettings::default();
+    assert_eq!(settings.hold_offset(300, 300, 100, 100), (170, 130));
+  }
+
+  #[test]
+  fn stash_defaults_to_the_bottom_left_corner() {
+    let settings = CarrySettings::default();
+    let bounds = Rect::new(0, 0, 1920, 1080);
+    assert_eq!(settings.stash_target(bounds, 300, 300), (0, 780));
+  }
+
+  #[test]
+  fn stash_point_is_kept_on_screen() {
+    let settings = CarrySettings {
+      stash: Some(StashPoint { x: 1900, y: -50 }),
+      ..CarrySettings::default()
+    };
+    let bounds = Rect::new(0, 0, 1920, 1080);
+    assert_eq!(settings.stash_target(bounds, 300, 300), (1620, 0));
+  }
+}
@@ -0,0 +1,309 @@
+use crate::path_planner::{touching_or_overlapping, Rect};
+use serde::{Deserialize, Serialize};
+
+/// Cursor moves smaller than this do not take control back from the keyboard.
+const CURSOR_JITTER_PX: f64 = 4.0;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CatchSettings {
+  /// New pumpkins stop dropping after this; the round ends once the last one lands.
+  pub duration_secs: u64,
+  pub spawn_interval_ms: u64,
+  pub max_falling: usize,
+  pub item_size: u32,
+  /// Pixels per second squared.
+  pub gravity: f64,
+  pub max_fall_speed: f64,
+  /// Horizontal pixels per second the character can cover.
+  pub catcher_speed: f64,
+  pub nutrition_per_catch: u32,
+}
+
+impl Default for CatchSettings {
+  fn default() -> Self {
+    Self {
+      duration_secs: 30,
+      spawn_interval_ms: 800,
+      max_falling: 4,
+      item_size: 90,
+      gravity: 900.0,
+      max_fall_speed: 650.0,
+      catcher_speed: 900.0,
+      nutrition_per_catch: 3,
+    }
+  }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct FallingItem {
+  pub id: u64,
+  pub x: i32,
+  pub y: f64,
+  velocity: f64,
+}
+
+impl FallingItem {
+  pub fn rect(&self, size: u32) -> Rect {
+    Rect::new(self.x, self.y.round() as i32, size as i32, size as i32)
+  }
+}
+
+#[derive(Debug, Default)]
+pub struct CatchEvents {
+  pub spawned: Vec<FallingItem>,
+  pub caught: Vec<u64>,
+  pub missed: Vec<u64>,
+}
+
+/// Where the horizontal steering comes from. Whichever input changed last wins.
+#[derive(Debug, Default)]
+pub struct CatchInput {
+  direction: i32,
+  keys_active: bool,
+  last_cursor_x: Option<f64>,
+}
+
+impl CatchInput {
+  pub fn set_direction(&mut self, direction: i32) {
+    self.direction = direction.signum();
+    self.keys_active = true;
+  }
+
+  /// Target x for the character's left edge.
+  pub fn target_x(&mut self, current_x: i32, catcher_width: i32, cursor_x: Option<f64>) -> i32 {
+    if let Some(cursor_x) = cursor_x {
+      let moved = match self.last_cursor_x {
+        Some(last) => (cursor_x - last).abs() > CURSOR_JITTER_PX,
+        None => true,
+      };
+      if moved && self.last_cursor_x.is_some() {
+        self.keys_active = false;
+      }
+      if moved {
+        self.last_cursor_x = Some(cursor_x);
+      }
+    }
+
+    if self.keys_active {
+      return current_x + self.direction * catcher_width;
+    }
+    match self.last_cursor_x {
+      Some(cursor_x) => cursor_x.round() as i32 - catcher_width / 2,
+      None => current_x,
+    }
+  }
+}
+
+/// The falling-pumpkins simulation. It knows nothing about windows; the caller
+/// mirrors `items()` onto real windows after every `step`.
+#[derive(Debug)]
+pub struct CatchSim {
+  items: Vec<FallingItem>,
+  next_id: u64,
+  rng: u64,
+  elapsed_ms: u64,
+  since_spawn_ms: u64,
+  pub caught: u32,
+  pub missed: u32,
+}
+
+impl CatchSim {
+  pub fn new(seed: u64) -> Self {
+    Self {
+      items: Vec::new(),
+      next_id: 0,
+      rng: seed | 1,
+      elapsed_ms: 0,
+      since_spawn_ms: 0,
+      caught: 0,
+      missed: 0,
+    }
+  }
+
+  pub fn items(&self) -> &[FallingItem] {
+    &self.items
+  }
+
+  pub fn is_over(&self, settings: &CatchSettings) -> bool {
+    self.elapsed_ms >= settings.duration_secs.saturating_mul(1000) && self.items.is_empty()
+  }
+
+  fn next_random(&mut self) -> u64 {
+    self.rng ^= self.rng << 13;
+    self.rng ^= self.rng >> 7;
+    self.rng ^= self.rng << 17;
+    self.rng
+  }
+
+  /// Moves the catcher toward `target_x`, limited by the catcher speed.
+  pub fn catcher_x(
+    settings: &CatchSettings,
+    current_x: i32,
+    target_x: i32,
+    dt_ms: u64,
+    bounds: Rect,
+    width: i32,
+  ) -> i32 {
+    let max_step = (settings.catcher_speed * dt_ms as f64 / 1000.0).round().max(1.0) as i32;
+    let target_x = target_x.clamp(bounds.x, (bounds.right() - width).max(bounds.x));
+    current_x + (target_x - current_x).clamp(-max_step, max_step)
+  }
+
+  /// Advances the simulation by `dt_ms`: spawns new pumpkins at the top of
+  /// `bounds`, applies gravity, and resolves catches against `catcher` and
+  /// misses at the bottom edge.
+  pub fn step(&mut self, settings: &CatchSettings, dt_ms: u64, bounds: Rect, catcher: Rect) -> CatchEvents {
+    let mut events = CatchEvents::default();
+    let size = settings.item_size as i32;
+    self.elapsed_ms += dt_ms;
+    self.since_spawn_ms += dt_ms;
+
+    let spawning = self.elapsed_ms < settings.duration_secs.saturating_mul(1000);
+    if spawning && self.since_spawn_ms >= settings.spawn_interval_ms && self.items.len() < settings.max_falling {
+      self.since_spawn_ms = 0;
+      let span = (bounds.width - size).max(1) as u64;
+      let x = bounds.x + (self.next_random() % span) as i32;
+      self.next_id += 1;
+      let item = FallingItem {
+        id: self.next_id,
+        x,
+        y: (bounds.y - size) as f64,
+        velocity: 0.0,
+      };
+      self.items.push(item);
+      events.spawned.push(item);
+    }
+
+    let dt = dt_ms as f64 / 1000.0;
+    for item in &mut self.items {
+      item.velocity = (item.velocity + settings.gravity * dt).min(settings.max_fall_speed);
+      item.y += item.velocity * dt;
+    }
+
+    self.items.retain(|item| {
+      let rect = item.rect(settings.item_size);
+      let caught = touching_or_overlapping(
+        catcher.x,
+        catcher.y,
+        catcher.width,
+        catcher.height,
+        rect.x,
+        rect.y,
+        rect.width,
+        rect.height,
+      );
+      if caught {
+        events.caught.push(item.id);
+        return false;
+      }
+      if rect.y >= bounds.bottom() {
+        events.missed.push(item.id);
+        return false;
+      }
+      true
+    });
+
+    self.caught += events.caught.len() as u32;
+    self.missed += events.missed.len() as u32;
+    events
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const DT_MS: u64 = 100;
+
+  fn settings() -> CatchSettings {
+    CatchSettings {
+      duration_secs: 1,
+      spawn_interval_ms: 200,
+      item_size: 50,
+      ..CatchSettings::default()
+    }
+  }
+
+  fn bounds() -> Rect {
+    Rect::new(0, 0, 1000, 800)
+  }
+
+  /// Steps until the round is over and returns how many steps it took.
+  fn run(sim: &mut CatchSim, settings: &CatchSettings, catcher: Rect) -> u32 {
+    let mut steps = 0;
+    while !sim.is_over(settings) {
+      sim.step(settings, DT_MS, bounds(), catcher);
+      steps += 1;
+      assert!(steps < 1_000, "round never ended");
+    }
+    steps
+  }
+
+  #[test]
+  fn spawns_on_the_interval_above_the_screen() {
+    let settings = settings();
+    let mut sim = CatchSim::new(7);
+    let away = Rect::new(0, 5_000, 10, 10);
+
+    assert!(sim.step(&settings, DT_MS, bounds(), away).spawned.is_empty());
+    let events = sim.step(&settings, DT_MS, bounds(), away);
+    assert_eq!(events.spawned.len(), 1);
+    let item = events.spawned[0];
+    assert_eq!(item.y, -50.0);
+    assert!((0..950).contains(&item.x));
+
+    assert_eq!(sim.items().len(), 1);
+    assert!(sim.items()[0].y > item.y);
+  }
+
+  #[test]
+  fn everything_missed_when_the_catcher_is_away() {
+    let settings = settings();
+    let mut sim = CatchSim::new(7);
+    run(&mut sim, &settings, Rect::new(0, 5_000, 10, 10));
+
+    assert_eq!(sim.caught, 0);
+    assert_eq!(sim.missed, 4);
+    assert!(sim.items().is_empty());
+  }
+
+  #[test]
+  fn everything_caught_by_a_full_width_catcher() {
+    let settings = settings();
+    let mut sim = CatchSim::new(7);
+    run(&mut sim, &settings, Rect::new(0, 700, 1000, 100));
+
+    assert_eq!(sim.caught, 4);
+    assert_eq!(sim.missed, 0);
+  }
+
+  #[test]
+  fn the_round_waits_for_the_last_pumpkin() {
+    let settings = settings();
+    let mut sim = CatchSim::new(7);
+    let steps = run(&mut sim, &settings, Rect::new(0, 5_000, 10, 10));
+    assert!(steps as u64 * DT_MS > settings.duration_secs * 1000);
+  }
+
+  #[test]
+  fn catcher_moves_at_most_its_speed() {
+    let settings = settings();
+    assert_eq!(CatchSim::catcher_x(&settings, 0, 500, DT_MS, bounds(), 200), 90);
+    assert_eq!(CatchSim::catcher_x(&settings, 500, 480, DT_MS, bounds(), 200), 480);
+    assert_eq!(CatchSim::catcher_x(&settings, 760, 5_000, DT_MS, bounds(), 200), 800);
+  }
+
+  #[test]
+  fn whichever_input_changed_last_steers() {
+    let mut input = CatchInput::default();
+    input.set_direction(1);
+    assert_eq!(input.target_x(100, 200, Some(300.0)), 300);
+
+    assert_eq!(input.target_x(100, 200, Some(302.0)), 300);
+    assert_eq!(input.target_x(100, 200, Some(600.0)), 500);
+
+    input.set_direction(-3);
+    assert_eq!(input.target_x(100, 200, Some(600.0)), -100);
+  }
+}
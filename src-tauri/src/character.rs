@@ -0,0 +1,526 @@
+//! Character packs: a `character.json` manifest plus `images/` and `voice/`
+//! folders, either as a directory or a zip file in the app data dir. The
+//! bundled Speaki is the built-in pack and is served from the app assets.
+
+use crate::{food::is_plain_file_name, voice_select::VoiceSelection};
+use serde::{Deserialize, Serialize};
+use std::{
+  collections::HashSet,
+  fs,
+  io::Read,
+  path::{Path, PathBuf},
+  sync::{Mutex, OnceLock},
+};
+
+pub const MANIFEST_FILE_NAME: &str = "character.json";
+pub const PACKS_DIR_NAME: &str = "characters";
+pub const BUILT_IN_PACK_ID: &str = "speaki";
+const BUILT_IN_MANIFEST: &str = include_str!("../character.json");
+/// Largest single file read out of a pack, so a zip bomb cannot exhaust memory.
+pub const MAX_FILE_BYTES: u64 = 16 * 1024 * 1024;
+
+/// The frontend looks these up by id, so every pack has to provide them.
+pub const CORE_EXPRESSIONS: [&str; 8] = [
+  "smile", "smile2", "eating", "back", "blush", "crying", "crying2", "crying3",
+];
+
+static PACKS: OnceLock<Mutex<Vec<CharacterPack>>> = OnceLock::new();
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CharacterSettings {
+  /// Id of the active pack.
+  pub pack: String,
+}
+
+impl Default for CharacterSettings {
+  fn default() -> Self {
+    Self {
+      pack: BUILT_IN_PACK_ID.into(),
+    }
+  }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PackExpression {
+  pub id: String,
+  pub label: String,
+  /// File name under `images/`.
+  pub sprite: String,
+  /// File names under `voice/`.
+  #[serde(default)]
+  pub voices: Vec<String>,
+  /// Only shown once the bond level unlocks it.
+  #[serde(default)]
+  pub bond_unlock: bool,
+  /// PNG under `images/` whose opaque pixels are the clickable area.
+  #[serde(default)]
+  pub hit_mask: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VoiceSets {
+  pub drag: Vec<String>,
+  pub satiated_click: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Personality {
+  /// Scales how far the character wanders in one go.
+  pub wander_multiplier: f64,
+  pub voice_volume: f64,
+}
+
+impl Default for Personality {
+  fn default() -> Self {
+    Self {
+      wander_multiplier: 1.0,
+      voice_volume: 0.9,
+    }
+  }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CharacterManifest {
+  pub id: String,
+  pub name: String,
+  #[serde(default)]
+  pub author: Option<String>,
+  /// Widget width in pixels before the user resizes it.
+  pub default_size: u32,
+  pub expressions: Vec<PackExpression>,
+  #[serde(default)]
+  pub voice_sets: VoiceSets,
+  #[serde(default)]
+  pub personality: Personality,
+  #[serde(default)]
+  pub voice_selection: VoiceSelection,
+}
+
+#[derive(Clone, Debug)]
+pub enum PackSource {
+  BuiltIn,
+  Dir(PathBuf),
+  Zip(PathBuf),
+}
+
+#[derive(Clone, Debug)]
+pub struct CharacterPack {
+  pub manifest: CharacterManifest,
+  pub source: PackSource,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct PackSummary {
+  pub id: String,
+  pub name: String,
+  pub author: Option<String>,
+}
+
+fn is_pack_id(id: &str) -> bool {
+  !id.is_empty()
+    && id.len() <= 64
+    && id
+      .chars()
+      .all(|ch| ch.is_ascii_lowercase() || ch.is_ascii_digit() || ch == '_' || ch == '-')
+}
+
+impl CharacterManifest {
+  /// Every file the manifest points at, as `images/<name>` or `voice/<name>`.
+  pub fn referenced_files(&self) -> Vec<String> {
+    let mut files = Vec::new();
+    for expression in &self.expressions {
+      files.push(format!("images/{}", expression.sprite));
+      if let Some(hit_mask) = &expression.hit_mask {
+        files.push(format!("images/{hit_mask}"));
+      }
+      files.extend(expression.voices.iter().map(|voice| format!("voice/{voice}")));
+    }
+    let sets = self.voice_sets.drag.iter().chain(&self.voice_sets.satiated_click);
+    files.extend(sets.map(|voice| format!("voice/{voice}")));
+    files.sort();
+    files.dedup();
+    files
+  }
+
+  /// The clips of a named pool: `drag`, `satiated_click` or `expression:<id>`.
+  pub fn voice_pool(&self, pool: &str) -> Option<&[String]> {
+    match pool {
+      "drag" => Some(&self.voice_sets.drag),
+      "satiated_click" => Some(&self.voice_sets.satiated_click),
+      _ => {
+        let id = pool.strip_prefix("expression:")?;
+        let expression = self.expressions.iter().find(|expression| expression.id == id)?;
+        Some(&expression.voices)
+      }
+    }
+  }
+
+  /// Checks the manifest on its own, without looking at any files.
+  pub fn validate(&self) -> Result<(), String> {
+    if !is_pack_id(&self.id) {
+      return Err(format!(
+        "pack id `{}` must be lowercase letters, digits, `_` or `-`",
+        self.id
+      ));
+    }
+    if self.name.trim().is_empty() {
+      return Err("pack name is empty".into());
+    }
+    if !(140..=600).contains(&self.default_size) {
+      return Err(format!("default_size {} is outside 140..=600", self.default_size));
+    }
+
+    let mut seen = HashSet::new();
+    for expression in &self.expressions {
+      if !seen.insert(expression.id.as_str()) {
+        return Err(format!("duplicate expression id `{}`", expression.id));
+      }
+      let names = std::iter::once(&expression.sprite)
+        .chain(&expression.hit_mask)
+        .chain(&expression.voices);
+      for name in names {
+        if !is_plain_file_name(name) {
+          return Err(format!(
+            "expression `{}` file `{name}` must be a plain file name",
+            expression.id
+          ));
+        }
+      }
+    }
+    if let Some(missing) = CORE_EXPRESSIONS.iter().find(|id| !seen.contains(*id)) {
+      return Err(format!("missing core expression `{missing}`"));
+    }
+    let sets = self.voice_sets.drag.iter().chain(&self.voice_sets.satiated_click);
+    if let Some(voice) = sets.into_iter().find(|voice| !is_plain_file_name(voice)) {
+      return Err(format!("voice set file `{voice}` must be a plain file name"));
+    }
+
+    self.voice_selection.validate()?;
+    if let Some(voice) = self
+      .voice_selection
+      .clips
+      .keys()
+      .find(|voice| !is_plain_file_name(voice))
+    {
+      return Err(format!("voice_selection file `{voice}` must be a plain file name"));
+    }
+
+    let personality = &self.personality;
+    if !(0.1..=5.0).contains(&personality.wander_multiplier) {
+      return Err("personality.wander_multiplier must be within 0.1..=5".into());
+    }
+    if !(0.0..=1.0).contains(&personality.voice_volume) {
+      return Err("personality.voice_volume must be within 0..=1".into());
+    }
+    Ok(())
+  }
+}
+
+/// Reads everything from `reader`, failing instead of truncating when it
+/// holds more than `limit` bytes.
+pub fn read_limited(reader: impl Read, limit: u64) -> Result<Vec<u8>, String> {
+  let mut bytes = Vec::new();
+  reader
+    .take(limit.saturating_add(1))
+    .read_to_end(&mut bytes)
+    .map_err(|error| error.to_string())?;
+  if bytes.len() as u64 > limit {
+    return Err(format!("file is larger than {limit} bytes"));
+  }
+  Ok(bytes)
+}
+
+fn open_zip(path: &Path) -> Result<zip::ZipArchive<fs::File>, String> {
+  let file = fs::File::open(path).map_err(|error| error.to_string())?;
+  zip::ZipArchive::new(file).map_err(|error| error.to_string())
+}
+
+fn read_zip_entry(archive: &mut zip::ZipArchive<fs::File>, relative: &str) -> Result<Vec<u8>, String> {
+  let entry = archive.by_name(relative).map_err(|error| error.to_string())?;
+  read_limited(entry, MAX_FILE_BYTES)
+}
+
+impl PackSource {
+  /// Reads `relative` (`images/x.png`, `voice/y.WAV`, the manifest) from the pack.
+  pub fn read(&self, relative: &str) -> Result<Vec<u8>, String> {
+    match self {
+      PackSource::BuiltIn => Err("the built-in pack is served by the app".into()),
+      PackSource::Dir(dir) => {
+        let file = fs::File::open(dir.join(relative)).map_err(|error| error.to_string())?;
+        read_limited(file, MAX_FILE_BYTES)
+      }
+      PackSource::Zip(path) => read_zip_entry(&mut open_zip(path)?, relative),
+    }
+  }
+}
+
+fn parse_manifest(raw: &[u8]) -> Result<CharacterManifest, String> {
+  let manifest: CharacterManifest = serde_json::from_slice(raw).map_err(|error| error.to_string())?;
+  manifest.validate()?;
+  if manifest.id == BUILT_IN_PACK_ID {
+    return Err(format!(
+      "pack id `{BUILT_IN_PACK_ID}` is reserved for the built-in character"
+    ));
+  }
+  Ok(manifest)
+}
+
+fn open_pack(source: PackSource) -> Result<CharacterPack, String> {
+  let (manifest, missing) = match &source {
+    PackSource::BuiltIn => return Err("the built-in pack is not loaded from disk".into()),
+    PackSource::Dir(dir) => {
+      let manifest = parse_manifest(&source.read(MANIFEST_FILE_NAME)?)?;
+      let missing = manifest
+        .referenced_files()
+        .into_iter()
+        .find(|file| !dir.join(file).is_file());
+      (manifest, missing)
+    }
+    PackSource::Zip(path) => {
+      // One archive handle serves the manifest and every file check.
+      let mut archive = open_zip(path)?;
+      let manifest = parse_manifest(&read_zip_entry(&mut archive, MANIFEST_FILE_NAME)?)?;
+      let missing = manifest
+        .referenced_files()
+        .into_iter()
+        .find(|file| archive.index_for_name(file).is_none());
+      (manifest, missing)
+    }
+  };
+  if let Some(missing) = missing {
+    return Err(format!("missing file `{missing}`"));
+  }
+  Ok(CharacterPack { manifest, source })
+}
+
+fn built_in_pack() -> CharacterPack {
+  CharacterPack {
+    manifest: serde_json::from_str(BUILT_IN_MANIFEST).expect("bundled character.json must be valid"),
+    source: PackSource::BuiltIn,
+  }
+}
+
+fn packs_state() -> &'static Mutex<Vec<CharacterPack>> {
+  PACKS.get_or_init(|| Mutex::new(vec![built_in_pack()]))
+}
+
+/// Looks for packs in `<data_dir>/characters`. Broken packs are logged and
+/// skipped; the built-in pack is always available.
+pub fn load_packs(data_dir: &Path) {
+  let dir = data_dir.join(PACKS_DIR_NAME);
+  let mut packs = vec![built_in_pack()];
+  let mut entries: Vec<PathBuf> = fs::read_dir(&dir)
+    .map(|entries| {
+      entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .collect()
+    })
+    .unwrap_or_default();
+  entries.sort();
+
+  for path in entries {
+    let source = if path.is_dir() {
+      PackSource::Dir(path.clone())
+    } else if path.extension().is_some_and(|extension| extension == "zip") {
+      PackSource::Zip(path.clone())
+    } else {
+      continue;
+    };
+    match open_pack(source) {
+      Ok(pack) if packs.iter().any(|known| known.manifest.id == pack.manifest.id) => {
+        log::warn!("ignoring {}: duplicate pack id `{}`", path.display(), pack.manifest.id);
+      }
+      Ok(pack) => packs.push(pack),
+      Err(error) => log::warn!("ignoring character pack {}: {}", path.display(), error),
+    }
+  }
+
+  if let Ok(mut current) = packs_state().lock() {
+    *current = packs;
+  }
+}
+
+pub fn find_pack(id: &str) -> Option<CharacterPack> {
+  packs_state()
+    .lock()
+    .ok()
+    .and_then(|packs| packs.iter().find(|pack| pack.manifest.id == id).cloned())
+}
+
+/// The pack named in the settings, or the built-in one when it is gone.
+pub fn active_pack(settings: &CharacterSettings) -> CharacterPack {
+  find_pack(&settings.pack).unwrap_or_else(built_in_pack)
+}
+
+pub fn list_packs() -> Vec<PackSummary> {
+  packs_state()
+    .lock()
+    .map(|packs| {
+      packs
+        .iter()
+        .map(|pack| PackSummary {
+          id: pack.manifest.id.clone(),
+          name: pack.manifest.name.clone(),
+          author: pack.manifest.author.clone(),
+        })
+        .collect()
+    })
+    .unwrap_or_default()
+}
+
+fn percent_decode(value: &str) -> Option<String> {
+  let bytes = value.as_bytes();
+  let mut decoded = Vec::with_capacity(bytes.len());
+  let mut index = 0;
+  while index < bytes.len() {
+    if bytes[index] == b'%' {
+      let hex = value.get(index + 1..index + 3)?;
+      decoded.push(u8::from_str_radix(hex, 16).ok()?);
+      index += 3;
+    } else {
+      decoded.push(bytes[index]);
+      index += 1;
+    }
+  }
+  String::from_utf8(decoded).ok()
+}
+
+/// Splits a `pack://` request path, `/<pack id>/<images|voice>/<file>`, into
+/// the pack id and the file inside the pack.
+pub fn parse_asset_path(path: &str) -> Option<(String, String)> {
+  let mut parts = path.trim_start_matches('/').splitn(3, '/');
+  let id = percent_decode(parts.next()?)?;
+  let folder = parts.next()?;
+  let file = percent_decode(parts.next()?)?;
+  if !is_pack_id(&id) || !matches!(folder, "images" | "voice") || !is_plain_file_name(&file) {
+    return None;
+  }
+  Some((id, format!("{folder}/{file}")))
+}
+
+pub fn content_type(file: &str) -> &'static str {
+  let extension = file.rsplit('.').next().unwrap_or_default().to_ascii_lowercase();
+  match extension.as_str() {
+    "png" => "image/png",
+    "wav" => "audio/wav",
+    _ => "application/octet-stream",
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::io::Write;
+
+  fn test_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("character-test-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+  }
+
+  /// The bundled manifest under a different id, so it can be loaded from disk.
+  fn tester_manifest() -> CharacterManifest {
+    CharacterManifest {
+      id: "tester".into(),
+      ..built_in_pack().manifest
+    }
+  }
+
+  fn write_zip(path: &Path, files: &[(String, Vec<u8>)]) {
+    let mut writer = zip::ZipWriter::new(fs::File::create(path).unwrap());
+    for (name, bytes) in files {
+      writer
+        .start_file(name.as_str(), zip::write::SimpleFileOptions::default())
+        .unwrap();
+      writer.write_all(bytes).unwrap();
+    }
+    writer.finish().unwrap();
+  }
+
+  #[test]
+  fn bundled_manifest_is_valid() {
+    assert!(built_in_pack().manifest.validate().is_ok());
+  }
+
+  #[test]
+  fn asset_paths_must_stay_inside_the_pack() {
+    let cases = [
+      ("/tester/images/smile.png", Some(("tester", "images/smile.png"))),
+      (
+        "/tester/voice/hello%20there.WAV",
+        Some(("tester", "voice/hello there.WAV")),
+      ),
+      ("/tester/images/%2e%2e", None),
+      ("/tester/images/..%2Fcharacter.json", None),
+      ("/tester/images/%zz.png", None),
+      ("/tester/images/%4", None),
+      ("/tester/scripts/run.rhai", None),
+      ("/Tester/images/smile.png", None),
+      ("/%2e%2e/images/smile.png", None),
+      ("/tester/images", None),
+    ];
+    for (path, expected) in cases {
+      let expected = expected.map(|(id, file)| (id.to_string(), file.to_string()));
+      assert_eq!(parse_asset_path(path), expected, "{path}");
+    }
+  }
+
+  #[test]
+  fn reads_past_the_limit_fail() {
+    assert_eq!(read_limited(&b"1234"[..], 4).unwrap(), b"1234");
+    assert!(read_limited(&b"12345"[..], 4).is_err());
+  }
+
+  #[test]
+  fn directory_packs_need_every_referenced_file() {
+    let dir = test_dir("dir");
+    let manifest = tester_manifest();
+    fs::write(dir.join(MANIFEST_FILE_NAME), serde_json::to_vec(&manifest).unwrap()).unwrap();
+    let error = open_pack(PackSource::Dir(dir.clone())).err().unwrap();
+    assert!(error.starts_with("missing file"), "{error}");
+
+    for file in manifest.referenced_files() {
+      let path = dir.join(&file);
+      fs::create_dir_all(path.parent().unwrap()).unwrap();
+      fs::write(path, b"x").unwrap();
+    }
+    let pack = open_pack(PackSource::Dir(dir.clone())).unwrap();
+    assert_eq!(pack.manifest.id, "tester");
+    let _ = fs::remove_dir_all(dir);
+  }
+
+  #[test]
+  fn zip_packs_load_and_serve_files() {
+    let dir = test_dir("zip");
+    let manifest = tester_manifest();
+    let mut files = vec![(MANIFEST_FILE_NAME.to_string(), serde_json::to_vec(&manifest).unwrap())];
+    files.extend(
+      manifest
+        .referenced_files()
+        .into_iter()
+        .map(|file| (file, b"x".to_vec())),
+    );
+
+    let partial = dir.join("partial.zip");
+    write_zip(&partial, &files[..2]);
+    assert!(open_pack(PackSource::Zip(partial)).is_err());
+
+    let complete = dir.join("complete.zip");
+    write_zip(&complete, &files);
+    let pack = open_pack(PackSource::Zip(complete)).unwrap();
+    assert_eq!(pack.source.read(&files[1].0).unwrap(), b"x");
+    assert!(pack.source.read("images/nothing.png").is_err());
+    let _ = fs::remove_dir_all(dir);
+  }
+
+  #[test]
+  fn the_built_in_id_is_reserved() {
+    let raw = serde_json::to_vec(&built_in_pack().manifest).unwrap();
+    assert!(parse_manifest(&raw).is_err());
+  }
+}
@@ -0,0 +1,85 @@
+use chrono::{NaiveDateTime, Timelike};
+use serde::{Deserialize, Serialize};
+
+/// A daily stretch of local time in `HH:MM` format; it may wrap past midnight.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct QuietHours {
+  pub start: String,
+  pub end: String,
+}
+
+impl QuietHours {
+  /// Whether `minute_of_day` falls inside. Unparsable times never match.
+  pub fn contains(&self, minute_of_day: u32) -> bool {
+    match (parse_clock_time(&self.start), parse_clock_time(&self.end)) {
+      (Some(start), Some(end)) => in_daily_window(start, end, minute_of_day),
+      _ => false,
+    }
+  }
+}
+
+/// Parses `HH:MM` into minutes after midnight.
+pub fn parse_clock_time(value: &str) -> Option<u32> {
+  let (hours, minutes) = value.trim().split_once(':')?;
+  let hours: u32 = hours.parse().ok()?;
+  let minutes: u32 = minutes.parse().ok()?;
+  (hours < 24 && minutes < 60).then_some(hours * 60 + minutes)
+}
+
+/// Whether `minute_of_day` falls in `[start, end)`, wrapping past midnight.
+pub fn in_daily_window(start: u32, end: u32, minute_of_day: u32) -> bool {
+  if start <= end {
+    minute_of_day >= start && minute_of_day < end
+  } else {
+    minute_of_day >= start || minute_of_day < end
+  }
+}
+
+pub fn minute_of_day(now: NaiveDateTime) -> u32 {
+  now.hour() * 60 + now.minute()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parses_clock_times() {
+    assert_eq!(parse_clock_time("00:00"), Some(0));
+    assert_eq!(parse_clock_time(" 7:05 "), Some(425));
+    assert_eq!(parse_clock_time("23:59"), Some(1439));
+    assert_eq!(parse_clock_time("24:00"), None);
+    assert_eq!(parse_clock_time("12:60"), None);
+    assert_eq!(parse_clock_time("noon"), None);
+  }
+
+  #[test]
+  fn windows_include_the_start_and_exclude_the_end() {
+    assert!(!in_daily_window(480, 1020, 479));
+    assert!(in_daily_window(480, 1020, 480));
+    assert!(in_daily_window(480, 1020, 1019));
+    assert!(!in_daily_window(480, 1020, 1020));
+  }
+
+  #[test]
+  fn windows_wrap_past_midnight() {
+    let night = QuietHours {
+      start: "23:00".into(),
+      end: "07:00".into(),
+    };
+    assert!(!night.contains(1379));
+    assert!(night.contains(1380));
+    assert!(night.contains(0));
+    assert!(night.contains(419));
+    assert!(!night.contains(420));
+  }
+
+  #[test]
+  fn unparsable_hours_never_match() {
+    let broken = QuietHours {
+      start: "late".into(),
+      end: "07:00".into(),
+    };
+    assert!(!broken.contains(0));
+  }
+}
@@ -0,0 +1,233 @@
+use crate::path_planner::Rect;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CursorMode {
+  #[default]
+  Off,
+  /// Walks after the cursor, stopping a little short of it.
+  Follow,
+  /// Shies away when the cursor comes close.
+  Avoid,
+  /// Stays put but turns toward the cursor.
+  LookAt,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CursorSettings {
+  pub mode: CursorMode,
+  pub sample_interval_ms: u64,
+  /// Horizontal gap, in pixels, the character keeps to the cursor when following.
+  pub follow_distance: f64,
+  pub follow_speed: f64,
+  /// How close the cursor gets to the character before it backs off.
+  pub avoid_radius: f64,
+  /// How far away the character retreats before calming down.
+  pub avoid_clearance: f64,
+  pub avoid_speed: f64,
+  /// The cursor has to be this far to one side before the character turns.
+  pub look_deadzone: f64,
+}
+
+impl Default for CursorSettings {
+  fn default() -> Self {
+    Self {
+      mode: CursorMode::Off,
+      sample_interval_ms: 40,
+      follow_distance: 160.0,
+      follow_speed: 220.0,
+      avoid_radius: 120.0,
+      avoid_clearance: 260.0,
+      avoid_speed: 520.0,
+      look_deadzone: 24.0,
+    }
+  }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Facing {
+  Left,
+  Right,
+}
+
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CursorEvent {
+  WalkStarted,
+  WalkStopped,
+  Face { facing: Facing },
+}
+
+/// Distance from a point to the nearest edge of `rect`, 0 inside it.
+fn distance_to(rect: Rect, x: f64, y: f64) -> f64 {
+  let dx = (rect.x as f64 - x).max(x - rect.right() as f64).max(0.0);
+  let dy = (rect.y as f64 - y).max(y - rect.bottom() as f64).max(0.0);
+  dx.hypot(dy)
+}
+
+fn center_x(body: Rect) -> f64 {
+  body.x as f64 + body.width as f64 / 2.0
+}
+
+pub fn facing(settings: &CursorSettings, body: Rect, cursor_x: f64) -> Option<Facing> {
+  let offset = cursor_x - center_x(body);
+  if offset < -settings.look_deadzone {
+    Some(Facing::Left)
+  } else if offset > settings.look_deadzone {
+    Some(Facing::Right)
+  } else {
+    None
+  }
+}
+
+/// Horizontal walking toward or away from the cursor. Keeps track of whether
+/// a walk is under way so the character does not twitch at the thresholds.
+#[derive(Debug, Default)]
+pub struct CursorWalker {
+  walking: bool,
+}
+
+impl CursorWalker {
+  pub fn stop(&mut self) {
+    self.walking = false;
+  }
+
+  /// The horizontal step to take this tick, or `None` once the character is
+  /// where the mode wants it. `min_x`/`max_x` bound the window's left edge.
+  pub fn step(
+    &mut self,
+    settings: &CursorSettings,
+    body: Rect,
+    cursor: (f64, f64),
+    (min_x, max_x): (i32, i32),
+    dt_ms: u64,
+  ) -> Option<f64> {
+    let dt = dt_ms as f64 / 1000.0;
+    let offset = cursor.0 - center_x(body);
+    let (direction, speed) = match settings.mode {
+      CursorMode::Follow => {
+        let gap = offset.abs() - settings.follow_distance;
+        // Start once the cursor is clearly out of reach, stop on arrival.
+        let threshold = if self.walking {
+          0.0
+        } else {
+          settings.follow_distance * 0.25
+        };
+        self.walking = gap > threshold;
+        (offset.signum(), settings.follow_speed.min(gap.max(0.0) / dt.max(0.001)))
+      }
+      CursorMode::Avoid => {
+        // A cursor resting on the character means it is being touched, not chased.
+        if body.contains_point(cursor.0.round() as i32, cursor.1.round() as i32) {
+          self.walking = false;
+          return None;
+        }
+        let distance = distance_to(body, cursor.0, cursor.1);
+        let limit = if self.walking {
+          settings.avoid_clearance
+        } else {
+          settings.avoid_radius
+        };
+        self.walking = distance < limit;
+        let away = if offset == 0.0 {
+          // Straight above or below: run toward the roomier side.
+          if (body.x - min_x) > (max_x - body.x) {
+            -1.0
+          } else {
+            1.0
+          }
+        } else {
+          -offset.signum()
+        };
+        (away, settings.avoid_speed)
+      }
+      CursorMode::Off | CursorMode::LookAt => {
+        self.walking = false;
+        (0.0, 0.0)
+      }
+    };
+    if !self.walking {
+      return None;
+    }
+
+    let target = (body.x as f64 + direction * speed * dt).clamp(min_x as f64, max_x.max(min_x) as f64);
+    let step = target - body.x as f64;
+    if step.abs() < 0.5 {
+      // Cornered against a screen edge.
+      self.walking = false;
+      return None;
+    }
+    Some(step)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const BOUNDS: (i32, i32) = (0, 1820);
+
+  fn settings(mode: CursorMode) -> CursorSettings {
+    CursorSettings {
+      mode,
+      ..CursorSettings::default()
+    }
+  }
+
+  #[test]
+  fn turns_only_outside_the_deadzone() {
+    let settings = CursorSettings::default();
+    let body = Rect::new(100, 0, 100, 100);
+    assert_eq!(facing(&settings, body, 100.0), Some(Facing::Left));
+    assert_eq!(facing(&settings, body, 160.0), None);
+    assert_eq!(facing(&settings, body, 200.0), Some(Facing::Right));
+  }
+
+  #[test]
+  fn follow_starts_past_the_threshold_and_stops_on_arrival() {
+    let settings = settings(CursorMode::Follow);
+    let body = Rect::new(0, 0, 100, 100);
+    let mut walker = CursorWalker::default();
+
+    assert_eq!(walker.step(&settings, body, (250.0, 50.0), BOUNDS, 100), None);
+    assert_eq!(walker.step(&settings, body, (500.0, 50.0), BOUNDS, 100), Some(22.0));
+    assert_eq!(walker.step(&settings, body, (230.0, 50.0), BOUNDS, 100), Some(20.0));
+    assert_eq!(walker.step(&settings, body, (210.0, 50.0), BOUNDS, 100), None);
+  }
+
+  #[test]
+  fn avoid_backs_off_until_clear() {
+    let settings = settings(CursorMode::Avoid);
+    let body = Rect::new(500, 0, 100, 100);
+    let mut walker = CursorWalker::default();
+
+    assert_eq!(walker.step(&settings, body, (800.0, 50.0), BOUNDS, 100), None);
+    assert_eq!(walker.step(&settings, body, (650.0, 50.0), BOUNDS, 100), Some(-52.0));
+    assert_eq!(walker.step(&settings, body, (800.0, 50.0), BOUNDS, 100), Some(-52.0));
+    assert_eq!(walker.step(&settings, body, (900.0, 50.0), BOUNDS, 100), None);
+  }
+
+  #[test]
+  fn avoid_ignores_touches_and_gives_up_when_cornered() {
+    let settings = settings(CursorMode::Avoid);
+    let mut walker = CursorWalker::default();
+
+    let body = Rect::new(500, 0, 100, 100);
+    assert_eq!(walker.step(&settings, body, (550.0, 50.0), BOUNDS, 100), None);
+
+    let cornered = Rect::new(0, 0, 100, 100);
+    assert_eq!(walker.step(&settings, cornered, (110.0, 50.0), BOUNDS, 100), None);
+  }
+
+  #[test]
+  fn look_at_and_off_never_walk() {
+    let body = Rect::new(0, 0, 100, 100);
+    for mode in [CursorMode::LookAt, CursorMode::Off] {
+      let mut walker = CursorWalker::default();
+      assert_eq!(walker.step(&settings(mode), body, (900.0, 50.0), BOUNDS, 100), None);
+    }
+  }
+}
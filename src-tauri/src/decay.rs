@@ -0,0 +1,185 @@
+use crate::clock::{in_daily_window, parse_clock_time};
+use serde::{Deserialize, Serialize};
+
+/// A stretch of the day with its own decay rate.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DecaySegment {
+  /// Local times in `HH:MM` format; the segment may wrap past midnight.
+  pub start: String,
+  pub end: String,
+  pub per_minute: f64,
+}
+
+/// How fast a need drops while nobody tends to the character.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DecayCurve {
+  /// The same number of points every minute.
+  Linear { per_minute: f64 },
+  /// Loses half of whatever is left every `half_life_minutes`, so a full
+  /// character gets hungry fast and a starving one slowly.
+  Exponential { half_life_minutes: f64 },
+  /// Linear, with the rate picked by the local time of day. Times outside
+  /// every segment use `default_per_minute`.
+  TimeOfDay {
+    segments: Vec<DecaySegment>,
+    default_per_minute: f64,
+  },
+}
+
+impl Default for DecayCurve {
+  fn default() -> Self {
+    DecayCurve::Linear { per_minute: 10.0 }
+  }
+}
+
+impl DecayCurve {
+  /// Points lost over `minutes`, starting from `current` at `minute_of_day`.
+  pub fn decay(&self, current: f64, minutes: f64, minute_of_day: u32) -> f64 {
+    let minutes = minutes.max(0.0);
+    match self {
+      DecayCurve::Linear { per_minute } => per_minute.max(0.0) * minutes,
+      DecayCurve::Exponential { half_life_minutes } => {
+        if *half_life_minutes <= 0.0 {
+          return current;
+        }
+        current * (1.0 - 0.5f64.powf(minutes / half_life_minutes))
+      }
+      DecayCurve::TimeOfDay {
+        segments,
+        default_per_minute,
+      } => {
+        let per_minute = segments
+          .iter()
+          .find(
+            |segment| match (parse_clock_time(&segment.start), parse_clock_time(&segment.end)) {
+              (Some(start), Some(end)) => in_daily_window(start, end, minute_of_day),
+              _ => false,
+            },
+          )
+          .map_or(*default_per_minute, |segment| segment.per_minute);
+        per_minute.max(0.0) * minutes
+      }
+    }
+  }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Difficulty {
+  Relaxed,
+  #[default]
+  Normal,
+  Hard,
+}
+
+impl Difficulty {
+  /// Scales every need that drains on its own.
+  pub fn drain_multiplier(self) -> f64 {
+    match self {
+      Difficulty::Relaxed => 0.5,
+      Difficulty::Normal => 1.0,
+      Difficulty::Hard => 1.6,
+    }
+  }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DecaySettings {
+  pub hunger: DecayCurve,
+  pub difficulty: Difficulty,
+  /// Stop all drift while the screen is locked or nobody has touched the
+  /// mouse or keyboard for `away_after_secs`.
+  pub pause_when_away: bool,
+  pub away_after_secs: u64,
+}
+
+impl Default for DecaySettings {
+  fn default() -> Self {
+    Self {
+      hunger: DecayCurve::default(),
+      difficulty: Difficulty::default(),
+      pause_when_away: true,
+      away_after_secs: 300,
+    }
+  }
+}
+
+impl DecaySettings {
+  pub fn is_away(&self, idle_ms: Option<u64>, locked: bool) -> bool {
+    locked || idle_ms.is_some_and(|idle_ms| idle_ms >= self.away_after_secs.saturating_mul(1000))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::clock::minute_of_day;
+  use chrono::{NaiveDate, NaiveDateTime};
+
+  fn at(day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2026, 3, day)
+      .and_then(|date| date.and_hms_opt(hour, minute, 0))
+      .unwrap()
+  }
+
+  fn overnight() -> DecayCurve {
+    DecayCurve::TimeOfDay {
+      segments: vec![DecaySegment {
+        start: "22:00".into(),
+        end: "06:00".into(),
+        per_minute: 2.0,
+      }],
+      default_per_minute: 10.0,
+    }
+  }
+
+  #[test]
+  fn time_of_day_rate_changes_at_the_segment_edges() {
+    let curve = overnight();
+    let rate = |now: NaiveDateTime| curve.decay(100.0, 1.0, minute_of_day(now));
+
+    assert_eq!(rate(at(1, 21, 59)), 10.0);
+    assert_eq!(rate(at(1, 22, 0)), 2.0);
+    assert_eq!(rate(at(1, 23, 59)), 2.0);
+    assert_eq!(rate(at(2, 0, 0)), 2.0);
+    assert_eq!(rate(at(2, 5, 59)), 2.0);
+    assert_eq!(rate(at(2, 6, 0)), 10.0);
+  }
+
+  #[test]
+  fn segments_with_bad_times_fall_back_to_the_default() {
+    let curve = DecayCurve::TimeOfDay {
+      segments: vec![DecaySegment {
+        start: "25:00".into(),
+        end: "06:00".into(),
+        per_minute: 2.0,
+      }],
+      default_per_minute: 10.0,
+    };
+    assert_eq!(curve.decay(100.0, 3.0, minute_of_day(at(1, 1, 0))), 30.0);
+  }
+
+  #[test]
+  fn linear_ignores_the_level_and_exponential_halves_it() {
+    let linear = DecayCurve::Linear { per_minute: 10.0 };
+    assert_eq!(linear.decay(80.0, 1.5, 0), 15.0);
+    assert_eq!(linear.decay(80.0, -1.0, 0), 0.0);
+
+    let exponential = DecayCurve::Exponential {
+      half_life_minutes: 30.0,
+    };
+    assert_eq!(exponential.decay(80.0, 30.0, 0), 40.0);
+    assert_eq!(exponential.decay(40.0, 30.0, 0), 20.0);
+  }
+
+  #[test]
+  fn away_after_the_idle_limit_or_when_locked() {
+    let settings = DecaySettings::default();
+    assert!(!settings.is_away(Some(299_999), false));
+    assert!(settings.is_away(Some(300_000), false));
+    assert!(settings.is_away(None, true));
+    assert!(!settings.is_away(None, false));
+  }
+}
@@ -0,0 +1,307 @@
+use crate::clock::{self, QuietHours};
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// How long after a scheduled time the slot may still fire, so starting the
+/// app in the evening does not replay the lunch feeding.
+const SCHEDULE_GRACE_MINUTES: u32 = 30;
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FeederSettings {
+  pub enabled: bool,
+  /// Local times in `HH:MM` format.
+  pub schedule: Vec<String>,
+  pub low_index_threshold: Option<u32>,
+  pub min_gap_minutes: u32,
+  pub daily_cap: u32,
+  pub quiet_hours: Option<QuietHours>,
+  /// Food id to spawn; `None` picks from the catalog at random.
+  pub food: Option<String>,
+}
+
+impl Default for FeederSettings {
+  fn default() -> Self {
+    Self {
+      enabled: false,
+      schedule: vec!["12:00".into(), "18:00".into()],
+      low_index_threshold: Some(20),
+      min_gap_minutes: 30,
+      daily_cap: 4,
+      quiet_hours: Some(QuietHours {
+        start: "23:00".into(),
+        end: "08:00".into(),
+      }),
+      food: Some("pumpkin".into()),
+    }
+  }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FeedReason {
+  /// The scheduled slot, in minutes after midnight.
+  Schedule(u32),
+  LowIndex,
+}
+
+/// Today's spawns as saved, so restarting the app neither resets the daily cap
+/// nor replays a slot that already fired.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FeederRecord {
+  /// `YYYY-MM-DD` the count belongs to.
+  pub day: Option<String>,
+  pub spawned: u32,
+  /// Scheduled slots that fired that day, in minutes after midnight.
+  pub fired_slots: Vec<u32>,
+}
+
+#[derive(Default)]
+pub struct FeederState {
+  day: Option<NaiveDate>,
+  spawned_today: u32,
+  fired_slots: HashSet<u32>,
+  last_spawn_at: Option<NaiveDateTime>,
+}
+
+impl FeederState {
+  /// Picks up today's count and fired slots from the save. A record from an
+  /// earlier day is dropped by the next `decide`.
+  pub fn resume(record: &FeederRecord) -> Self {
+    Self {
+      day: record
+        .day
+        .as_deref()
+        .and_then(|day| NaiveDate::parse_from_str(day, DATE_FORMAT).ok()),
+      spawned_today: record.spawned,
+      fired_slots: record.fired_slots.iter().copied().collect(),
+      last_spawn_at: None,
+    }
+  }
+
+  pub fn record(&self) -> FeederRecord {
+    let mut fired_slots: Vec<u32> = self.fired_slots.iter().copied().collect();
+    fired_slots.sort_unstable();
+    FeederRecord {
+      day: self.day.map(|day| day.format(DATE_FORMAT).to_string()),
+      spawned: self.spawned_today,
+      fired_slots,
+    }
+  }
+
+  /// Decides whether the feeder should spawn food right now. Nothing counts
+  /// against the cap until `record_spawn` confirms the food appeared.
+  pub fn decide(
+    &mut self,
+    settings: &FeederSettings,
+    now: NaiveDateTime,
+    pumpkin_index: u32,
+    food_present: bool,
+  ) -> Option<FeedReason> {
+    if self.day != Some(now.date()) {
+      self.day = Some(now.date());
+      self.spawned_today = 0;
+      self.fired_slots.clear();
+    }
+
+    if !settings.enabled || food_present || self.spawned_today >= settings.daily_cap {
+      return None;
+    }
+
+    let minute_of_day = clock::minute_of_day(now);
+    if settings
+      .quiet_hours
+      .as_ref()
+      .is_some_and(|quiet| quiet.contains(minute_of_day))
+    {
+      return None;
+    }
+
+    let due_slot = settings
+      .schedule
+      .iter()
+      .filter_map(|slot| clock::parse_clock_time(slot))
+      .find(|slot| {
+        !self.fired_slots.contains(slot) && minute_of_day >= *slot && minute_of_day - *slot <= SCHEDULE_GRACE_MINUTES
+      });
+    if let Some(slot) = due_slot {
+      return Some(FeedReason::Schedule(slot));
+    }
+
+    let gap_elapsed = self
+      .last_spawn_at
+      .map(|last| (now - last).num_minutes() >= settings.min_gap_minutes as i64)
+      .unwrap_or(true);
+    let is_low = settings
+      .low_index_threshold
+      .is_some_and(|threshold| pumpkin_index <= threshold);
+    if is_low && gap_elapsed {
+      return Some(FeedReason::LowIndex);
+    }
+
+    None
+  }
+
+  /// Counts a spawn that `decide` asked for against today's cap.
+  pub fn record_spawn(&mut self, reason: FeedReason, now: NaiveDateTime) {
+    if let FeedReason::Schedule(slot) = reason {
+      self.fired_slots.insert(slot);
+    }
+    self.spawned_today += 1;
+    self.last_spawn_at = Some(now);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const FULL: u32 = 80;
+
+  fn at(day: u32, time: &str) -> NaiveDateTime {
+    NaiveDateTime::parse_from_str(&format!("2026-03-{day:02} {time}"), "%Y-%m-%d %H:%M").unwrap()
+  }
+
+  fn enabled() -> FeederSettings {
+    FeederSettings {
+      enabled: true,
+      ..FeederSettings::default()
+    }
+  }
+
+  /// Runs `decide` and, like the worker, records every spawn it asks for.
+  fn feed(state: &mut FeederState, settings: &FeederSettings, now: NaiveDateTime, index: u32) -> Option<FeedReason> {
+    let reason = state.decide(settings, now, index, false)?;
+    state.record_spawn(reason, now);
+    Some(reason)
+  }
+
+  #[test]
+  fn scheduled_slots_fire_once_within_the_grace_window() {
+    let settings = enabled();
+    let mut state = FeederState::default();
+
+    assert_eq!(feed(&mut state, &settings, at(2, "11:59"), FULL), None);
+    assert_eq!(
+      feed(&mut state, &settings, at(2, "12:30"), FULL),
+      Some(FeedReason::Schedule(720))
+    );
+    assert_eq!(feed(&mut state, &settings, at(2, "12:31"), FULL), None);
+    assert_eq!(feed(&mut state, &settings, at(2, "18:31"), FULL), None);
+    assert_eq!(
+      feed(&mut state, &settings, at(3, "18:30"), FULL),
+      Some(FeedReason::Schedule(1080))
+    );
+  }
+
+  #[test]
+  fn unrecorded_decisions_do_not_use_up_the_slot() {
+    let settings = enabled();
+    let mut state = FeederState::default();
+    assert_eq!(
+      state.decide(&settings, at(2, "12:00"), FULL, false),
+      Some(FeedReason::Schedule(720))
+    );
+    assert_eq!(
+      state.decide(&settings, at(2, "12:05"), FULL, false),
+      Some(FeedReason::Schedule(720))
+    );
+    assert_eq!(state.decide(&settings, at(2, "12:05"), FULL, true), None);
+  }
+
+  #[test]
+  fn low_index_waits_for_the_gap() {
+    let settings = enabled();
+    let mut state = FeederState::default();
+
+    assert_eq!(feed(&mut state, &settings, at(2, "09:00"), 21), None);
+    assert_eq!(
+      feed(&mut state, &settings, at(2, "09:00"), 20),
+      Some(FeedReason::LowIndex)
+    );
+    assert_eq!(feed(&mut state, &settings, at(2, "09:29"), 0), None);
+    assert_eq!(
+      feed(&mut state, &settings, at(2, "09:30"), 0),
+      Some(FeedReason::LowIndex)
+    );
+  }
+
+  #[test]
+  fn daily_cap_resets_at_midnight() {
+    let settings = FeederSettings {
+      daily_cap: 2,
+      quiet_hours: None,
+      ..enabled()
+    };
+    let mut state = FeederState::default();
+
+    assert!(feed(&mut state, &settings, at(2, "09:00"), 0).is_some());
+    assert!(feed(&mut state, &settings, at(2, "10:00"), 0).is_some());
+    assert_eq!(feed(&mut state, &settings, at(2, "12:00"), 0), None);
+    assert_eq!(feed(&mut state, &settings, at(2, "23:59"), 0), None);
+    assert_eq!(
+      feed(&mut state, &settings, at(3, "00:00"), 0),
+      Some(FeedReason::LowIndex)
+    );
+  }
+
+  #[test]
+  fn quiet_hours_wrap_past_midnight() {
+    let settings = FeederSettings {
+      schedule: vec!["23:30".into(), "07:45".into()],
+      ..enabled()
+    };
+    let mut state = FeederState::default();
+
+    assert_eq!(
+      feed(&mut state, &settings, at(2, "22:59"), 0),
+      Some(FeedReason::LowIndex)
+    );
+    assert_eq!(feed(&mut state, &settings, at(2, "23:30"), 0), None);
+    assert_eq!(feed(&mut state, &settings, at(3, "03:00"), 0), None);
+    assert_eq!(feed(&mut state, &settings, at(3, "07:59"), 0), None);
+    assert_eq!(
+      feed(&mut state, &settings, at(3, "08:00"), FULL),
+      Some(FeedReason::Schedule(465))
+    );
+  }
+
+  #[test]
+  fn resuming_keeps_the_count_and_fired_slots() {
+    let settings = FeederSettings {
+      daily_cap: 2,
+      ..enabled()
+    };
+    let mut state = FeederState::default();
+    assert_eq!(
+      feed(&mut state, &settings, at(2, "12:00"), FULL),
+      Some(FeedReason::Schedule(720))
+    );
+
+    let record = state.record();
+    assert_eq!(record.day.as_deref(), Some("2026-03-02"));
+    assert_eq!((record.spawned, record.fired_slots.as_slice()), (1, &[720][..]));
+
+    let mut resumed = FeederState::resume(&record);
+    assert_eq!(resumed.decide(&settings, at(2, "12:10"), FULL, false), None);
+    assert_eq!(
+      feed(&mut resumed, &settings, at(2, "12:10"), 0),
+      Some(FeedReason::LowIndex)
+    );
+    assert_eq!(feed(&mut resumed, &settings, at(2, "18:00"), FULL), None);
+
+    let mut next_day = FeederState::resume(&record);
+    assert_eq!(
+      feed(&mut next_day, &settings, at(3, "12:00"), FULL),
+      Some(FeedReason::Schedule(720))
+    );
+  }
+
+  #[test]
+  fn disabled_feeder_never_fires() {
+    let mut state = FeederState::default();
+    assert_eq!(feed(&mut state, &FeederSettings::default(), at(2, "12:00"), 0), None);
+  }
+}
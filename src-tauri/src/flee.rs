@@ -0,0 +1,216 @@
+use crate::path_planner::Rect;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FleeSettings {
+  pub time_limit_secs: u64,
+  /// Pixels per tick the pumpkin runs at in open space.
+  pub flee_speed_px: f64,
+  /// Pixels per tick the character chases at before gait scaling.
+  pub chaser_speed_px: f64,
+  /// Threats farther than this from the pumpkin's center are ignored.
+  pub flee_radius: f64,
+  pub avoid_cursor: bool,
+  /// How strongly the cursor scares the pumpkin compared to the character.
+  pub cursor_weight: f64,
+  /// Within this many pixels of a screen edge the pumpkin slows down, which is
+  /// what makes cornering it possible.
+  pub wall_slow_zone: f64,
+}
+
+impl Default for FleeSettings {
+  fn default() -> Self {
+    Self {
+      time_limit_secs: 20,
+      flee_speed_px: 7.0,
+      chaser_speed_px: 6.0,
+      flee_radius: 420.0,
+      avoid_cursor: true,
+      cursor_weight: 0.7,
+      wall_slow_zone: 160.0,
+    }
+  }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Threat {
+  pub x: f64,
+  pub y: f64,
+  pub weight: f64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+pub enum FleeOutcome {
+  Caught { elapsed_ms: u64 },
+  Escaped,
+}
+
+/// Persisted flee game results.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FleeRecord {
+  pub wins: u32,
+  pub losses: u32,
+  pub best_time_ms: Option<u64>,
+}
+
+impl FleeRecord {
+  pub fn record(&mut self, outcome: FleeOutcome) {
+    match outcome {
+      FleeOutcome::Caught { elapsed_ms } => {
+        self.wins += 1;
+        self.best_time_ms = Some(self.best_time_ms.map_or(elapsed_ms, |best| best.min(elapsed_ms)));
+      }
+      FleeOutcome::Escaped => self.losses += 1,
+    }
+  }
+}
+
+impl FleeSettings {
+  /// Ends the round once the pumpkin is caught or time runs out.
+  pub fn outcome(&self, elapsed_ms: u64, caught: bool) -> Option<FleeOutcome> {
+    if caught {
+      return Some(FleeOutcome::Caught { elapsed_ms });
+    }
+    (elapsed_ms >= self.time_limit_secs.saturating_mul(1000)).then_some(FleeOutcome::Escaped)
+  }
+
+  /// Next top-left position of the pumpkin window. Each threat pushes the
+  /// pumpkin away, harder the closer it is; the screen edges clamp the result
+  /// so a pumpkin pressed against a wall slides along it.
+  pub fn flee_step(&self, pumpkin: Rect, threats: &[Threat], bounds: Rect) -> (i32, i32) {
+    let center_x = pumpkin.x as f64 + pumpkin.width as f64 / 2.0;
+    let center_y = pumpkin.y as f64 + pumpkin.height as f64 / 2.0;
+
+    let mut push_x = 0.0;
+    let mut push_y = 0.0;
+    for threat in threats {
+      let dx = center_x - threat.x;
+      let dy = center_y - threat.y;
+      let distance = dx.hypot(dy).max(1.0);
+      if distance >= self.flee_radius {
+        continue;
+      }
+      let strength = threat.weight * (1.0 - distance / self.flee_radius);
+      push_x += dx / distance * strength;
+      push_y += dy / distance * strength;
+    }
+
+    let push = push_x.hypot(push_y);
+    if push < 0.01 {
+      return (pumpkin.x, pumpkin.y);
+    }
+
+    let wall_distance = [
+      pumpkin.x - bounds.x,
+      bounds.right() - pumpkin.right(),
+      pumpkin.y - bounds.y,
+      bounds.bottom() - pumpkin.bottom(),
+    ]
+    .into_iter()
+    .min()
+    .unwrap_or(0)
+    .max(0) as f64;
+    let wall_factor = if self.wall_slow_zone > 0.0 {
+      (wall_distance / self.wall_slow_zone).clamp(0.35, 1.0)
+    } else {
+      1.0
+    };
+    let speed = self.flee_speed_px * push.min(1.0) * wall_factor;
+
+    let next_x = (pumpkin.x as f64 + push_x / push * speed).round() as i32;
+    let next_y = (pumpkin.y as f64 + push_y / push * speed).round() as i32;
+    (
+      next_x.clamp(bounds.x, (bounds.right() - pumpkin.width).max(bounds.x)),
+      next_y.clamp(bounds.y, (bounds.bottom() - pumpkin.height).max(bounds.y)),
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn screen() -> Rect {
+    Rect::new(0, 0, 1920, 1080)
+  }
+
+  fn threat(x: f64, y: f64) -> Threat {
+    Threat { x, y, weight: 1.0 }
+  }
+
+  #[test]
+  fn runs_directly_away_from_a_close_threat() {
+    let settings = FleeSettings::default();
+    let pumpkin = Rect::new(500, 500, 100, 100);
+    assert_eq!(
+      settings.flee_step(pumpkin, &[threat(450.0, 550.0)], screen()),
+      (505, 500)
+    );
+    assert_eq!(
+      settings.flee_step(pumpkin, &[threat(550.0, 650.0)], screen()),
+      (500, 495)
+    );
+  }
+
+  #[test]
+  fn ignores_threats_outside_the_radius() {
+    let settings = FleeSettings::default();
+    let pumpkin = Rect::new(500, 500, 100, 100);
+    assert_eq!(
+      settings.flee_step(pumpkin, &[threat(50.0, 550.0)], screen()),
+      (500, 500)
+    );
+    assert_eq!(settings.flee_step(pumpkin, &[], screen()), (500, 500));
+  }
+
+  #[test]
+  fn opposite_threats_cancel_out() {
+    let settings = FleeSettings::default();
+    let pumpkin = Rect::new(500, 500, 100, 100);
+    let threats = [threat(450.0, 550.0), threat(650.0, 550.0)];
+    assert_eq!(settings.flee_step(pumpkin, &threats, screen()), (500, 500));
+  }
+
+  #[test]
+  fn slows_near_walls_and_stays_on_screen() {
+    let settings = FleeSettings::default();
+    let near_wall = Rect::new(10, 500, 100, 100);
+    let (x, y) = settings.flee_step(near_wall, &[threat(160.0, 550.0)], screen());
+    assert_eq!((x, y), (8, 500));
+
+    let at_wall = Rect::new(0, 500, 100, 100);
+    assert_eq!(settings.flee_step(at_wall, &[threat(100.0, 550.0)], screen()), (0, 500));
+
+    let corner = Rect::new(1820, 980, 100, 100);
+    assert_eq!(
+      settings.flee_step(corner, &[threat(1800.0, 1000.0)], screen()),
+      (1820, 980)
+    );
+  }
+
+  #[test]
+  fn round_ends_on_catch_or_time_limit() {
+    let settings = FleeSettings::default();
+    assert_eq!(
+      settings.outcome(1_500, true),
+      Some(FleeOutcome::Caught { elapsed_ms: 1_500 })
+    );
+    assert_eq!(settings.outcome(19_999, false), None);
+    assert_eq!(settings.outcome(20_000, false), Some(FleeOutcome::Escaped));
+  }
+
+  #[test]
+  fn record_keeps_the_fastest_catch() {
+    let mut record = FleeRecord::default();
+    record.record(FleeOutcome::Caught { elapsed_ms: 9_000 });
+    record.record(FleeOutcome::Caught { elapsed_ms: 4_000 });
+    record.record(FleeOutcome::Caught { elapsed_ms: 6_000 });
+    record.record(FleeOutcome::Escaped);
+
+    assert_eq!((record.wins, record.losses), (3, 1));
+    assert_eq!(record.best_time_ms, Some(4_000));
+  }
+}
@@ -0,0 +1,204 @@
+use serde::{Deserialize, Serialize};
+use std::{
+  collections::HashSet,
+  fs,
+  path::Path,
+  sync::{Mutex, OnceLock},
+};
+
+const CATALOG_FILE_NAME: &str = "foods.json";
+const DEFAULT_CATALOG: &str = include_str!("../foods.json");
+
+static FOOD_CATALOG: OnceLock<Mutex<FoodCatalog>> = OnceLock::new();
+
+/// A bare file name with no directory part, so it cannot leave its asset folder.
+pub fn is_plain_file_name(name: &str) -> bool {
+  !name.is_empty() && name != "." && name != ".." && !name.contains('/') && !name.contains('\\')
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MoodEffect {
+  Happy,
+  FullRefill,
+  Disliked,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FoodItem {
+  pub id: String,
+  pub label: String,
+  /// File name under `web/images`.
+  pub sprite: String,
+  /// Optional CSS filter applied on top of the sprite.
+  #[serde(default)]
+  pub tint: Option<String>,
+  pub window_size: u32,
+  pub nutrition: u32,
+  pub mood_effect: MoodEffect,
+  /// Relative weight used when a food is picked at random.
+  pub likelihood: u32,
+  /// File name under `web/voice` played when the food is eaten.
+  #[serde(default)]
+  pub voice: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FoodCatalog {
+  pub foods: Vec<FoodItem>,
+}
+
+impl FoodCatalog {
+  fn validate(&self) -> Result<(), String> {
+    if self.foods.is_empty() {
+      return Err("food catalog is empty".into());
+    }
+
+    let mut seen = HashSet::new();
+    for food in &self.foods {
+      if !seen.insert(food.id.as_str()) {
+        return Err(format!("duplicate food id `{}`", food.id));
+      }
+      if !(40..=600).contains(&food.window_size) {
+        return Err(format!("food `{}` has window_size outside 40..=600", food.id));
+      }
+      if !is_plain_file_name(&food.sprite) {
+        return Err(format!("food `{}` sprite must be a plain file name", food.id));
+      }
+      if food.voice.as_deref().is_some_and(|voice| !is_plain_file_name(voice)) {
+        return Err(format!("food `{}` voice must be a plain file name", food.id));
+      }
+    }
+
+    Ok(())
+  }
+
+  pub fn find(&self, id: &str) -> Option<&FoodItem> {
+    self.foods.iter().find(|food| food.id == id)
+  }
+
+  /// Picks a food with probability proportional to its likelihood.
+  pub fn pick_weighted(&self, seed: u64) -> Option<&FoodItem> {
+    let total: u64 = self.foods.iter().map(|food| food.likelihood as u64).sum();
+    if total == 0 {
+      return self.foods.first();
+    }
+
+    let mut roll = seed % total;
+    for food in &self.foods {
+      let weight = food.likelihood as u64;
+      if roll < weight {
+        return Some(food);
+      }
+      roll -= weight;
+    }
+
+    self.foods.last()
+  }
+}
+
+fn default_catalog() -> FoodCatalog {
+  serde_json::from_str(DEFAULT_CATALOG).expect("bundled foods.json must be valid")
+}
+
+fn food_catalog_state() -> &'static Mutex<FoodCatalog> {
+  FOOD_CATALOG.get_or_init(|| Mutex::new(default_catalog()))
+}
+
+/// Loads `foods.json` from `config_dir` if present, keeping the bundled catalog
+/// when the override is missing or invalid.
+pub fn load_catalog(config_dir: &Path) {
+  let path = config_dir.join(CATALOG_FILE_NAME);
+  let Ok(raw) = fs::read_to_string(&path) else {
+    return;
+  };
+
+  let parsed = serde_json::from_str::<FoodCatalog>(&raw)
+    .map_err(|error| error.to_string())
+    .and_then(|catalog| catalog.validate().map(|_| catalog));
+  match parsed {
+    Ok(catalog) => {
+      if let Ok(mut current) = food_catalog_state().lock() {
+        *current = catalog;
+      }
+    }
+    Err(error) => log::warn!("ignoring {}: {}", path.display(), error),
+  }
+}
+
+pub fn current_catalog() -> FoodCatalog {
+  food_catalog_state()
+    .lock()
+    .map(|catalog| catalog.clone())
+    .unwrap_or_else(|_| default_catalog())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn food(id: &str, likelihood: u32) -> FoodItem {
+    FoodItem {
+      id: id.to_string(),
+      label: id.to_string(),
+      sprite: format!("{id}.png"),
+      tint: None,
+      window_size: 120,
+      nutrition: 10,
+      mood_effect: MoodEffect::Happy,
+      likelihood,
+      voice: None,
+    }
+  }
+
+  fn catalog(foods: Vec<FoodItem>) -> FoodCatalog {
+    FoodCatalog { foods }
+  }
+
+  #[test]
+  fn bundled_catalog_is_valid() {
+    assert_eq!(default_catalog().validate(), Ok(()));
+  }
+
+  #[test]
+  fn pick_weighted_follows_likelihood() {
+    let catalog = catalog(vec![food("pumpkin", 3), food("never", 0), food("apple", 1)]);
+    let picks: Vec<&str> = (0..4)
+      .map(|seed| catalog.pick_weighted(seed).unwrap().id.as_str())
+      .collect();
+    assert_eq!(picks, ["pumpkin", "pumpkin", "pumpkin", "apple"]);
+    assert_eq!(catalog.pick_weighted(7).unwrap().id, "apple");
+  }
+
+  #[test]
+  fn pick_weighted_falls_back_to_the_first_food_when_all_weights_are_zero() {
+    let catalog = catalog(vec![food("pumpkin", 0), food("apple", 0)]);
+    assert_eq!(catalog.pick_weighted(12345).unwrap().id, "pumpkin");
+    assert!(FoodCatalog { foods: Vec::new() }.pick_weighted(0).is_none());
+  }
+
+  #[test]
+  fn rejects_duplicate_ids() {
+    let error = catalog(vec![food("pumpkin", 1), food("pumpkin", 2)])
+      .validate()
+      .unwrap_err();
+    assert!(error.contains("duplicate food id `pumpkin`"), "{error}");
+  }
+
+  #[test]
+  fn requires_plain_file_names() {
+    for name in ["../secret.png", "images/pumpkin.png", "a\\b.png", "..", ""] {
+      let mut sprite = food("pumpkin", 1);
+      sprite.sprite = name.to_string();
+      assert!(catalog(vec![sprite]).validate().is_err(), "sprite {name:?}");
+
+      let mut voice = food("pumpkin", 1);
+      voice.voice = Some(name.to_string());
+      assert!(catalog(vec![voice]).validate().is_err(), "voice {name:?}");
+    }
+
+    let mut fine = food("pumpkin", 1);
+    fine.voice = Some("I like pumpkin.WAV".to_string());
+    assert_eq!(catalog(vec![fine]).validate(), Ok(()));
+  }
+}
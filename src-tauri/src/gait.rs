@@ -0,0 +1,222 @@
+use serde::{Deserialize, Serialize};
+
+const BASE_STEP_PX: f64 = 4.0;
+const BASE_GAIT_CYCLE_PX: f64 = 34.0;
+const BASE_DELAY_MS: f64 = 8.0;
+const EDGE_SLOW_DELAY_MS: f64 = 4.0;
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct GaitPoint {
+  pub pumpkin_index: u32,
+  /// Multiplier on the distance covered per step.
+  pub speed: f64,
+  /// Multiplier on both the wobble amplitude and the length of one gait cycle.
+  pub stride: f64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GaitSettings {
+  /// Control points sorted by pumpkin index; values in between are interpolated.
+  pub curve: Vec<GaitPoint>,
+  /// At or above this index the character is too full to walk to a pumpkin.
+  pub ignore_at_or_above: Option<u32>,
+  pub sulking_speed_factor: f64,
+}
+
+impl Default for GaitSettings {
+  fn default() -> Self {
+    Self {
+      curve: vec![
+        GaitPoint {
+          pumpkin_index: 0,
+          speed: 2.2,
+          stride: 1.7,
+        },
+        GaitPoint {
+          pumpkin_index: 30,
+          speed: 1.5,
+          stride: 1.3,
+        },
+        GaitPoint {
+          pumpkin_index: 60,
+          speed: 1.0,
+          stride: 1.0,
+        },
+        GaitPoint {
+          pumpkin_index: 100,
+          speed: 0.6,
+          stride: 0.8,
+        },
+      ],
+      ignore_at_or_above: Some(100),
+      sulking_speed_factor: 0.75,
+    }
+  }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Gait {
+  pub step_px: f64,
+  pub gait_cycle_px: f64,
+  pub wobble_scale: f64,
+  pub base_delay_ms: f64,
+  pub edge_slow_delay_ms: f64,
+}
+
+impl Gait {
+  pub fn speed_factor(&self) -> f64 {
+    self.step_px / BASE_STEP_PX
+  }
+
+  /// The same gait with shorter steps, never slower than the slowest curve point.
+  pub fn slowed(self, factor: f64) -> Self {
+    Self {
+      step_px: (self.step_px * factor).max(BASE_STEP_PX * 0.2),
+      ..self
+    }
+  }
+}
+
+impl Default for Gait {
+  fn default() -> Self {
+    Self {
+      step_px: BASE_STEP_PX,
+      gait_cycle_px: BASE_GAIT_CYCLE_PX,
+      wobble_scale: 1.0,
+      base_delay_ms: BASE_DELAY_MS,
+      edge_slow_delay_ms: EDGE_SLOW_DELAY_MS,
+    }
+  }
+}
+
+fn sample_curve(curve: &[GaitPoint], pumpkin_index: u32) -> (f64, f64) {
+  let Some(first) = curve.first() else {
+    return (1.0, 1.0);
+  };
+  if pumpkin_index <= first.pumpkin_index {
+    return (first.speed, first.stride);
+  }
+
+  for pair in curve.windows(2) {
+    let (low, high) = (pair[0], pair[1]);
+    if pumpkin_index <= high.pumpkin_index {
+      let span = high.pumpkin_index.saturating_sub(low.pumpkin_index).max(1) as f64;
+      let t = pumpkin_index.saturating_sub(low.pumpkin_index) as f64 / span;
+      return (
+        low.speed + (high.speed - low.speed) * t,
+        low.stride + (high.stride - low.stride) * t,
+      );
+    }
+  }
+
+  let last = curve[curve.len() - 1];
+  (last.speed, last.stride)
+}
+
+/// Picks the chase gait for the current pumpkin index and mood, or `None` when
+/// the character is satiated enough to ignore the pumpkin entirely.
+pub fn chase_gait(settings: &GaitSettings, pumpkin_index: u32, sulking: bool) -> Option<Gait> {
+  if let Some(threshold) = settings.ignore_at_or_above {
+    if pumpkin_index >= threshold && !sulking {
+      return None;
+    }
+  }
+
+  let (mut speed, stride) = sample_curve(&settings.curve, pumpkin_index);
+  if sulking {
+    speed *= settings.sulking_speed_factor;
+  }
+  let speed = speed.clamp(0.2, 5.0);
+  let stride = stride.clamp(0.3, 3.0);
+
+  Some(Gait {
+    step_px: BASE_STEP_PX * speed,
+    gait_cycle_px: BASE_GAIT_CYCLE_PX * stride,
+    wobble_scale: stride,
+    base_delay_ms: BASE_DELAY_MS,
+    edge_slow_delay_ms: EDGE_SLOW_DELAY_MS,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn speed(gait: Gait) -> f64 {
+    gait.speed_factor()
+  }
+
+  #[test]
+  fn interpolates_between_curve_points() {
+    let settings = GaitSettings::default();
+    let hungry = chase_gait(&settings, 0, false).unwrap();
+    let peckish = chase_gait(&settings, 15, false).unwrap();
+    let content = chase_gait(&settings, 60, false).unwrap();
+
+    assert!((speed(hungry) - 2.2).abs() < 1e-9);
+    assert!((speed(peckish) - 1.85).abs() < 1e-9);
+    assert!((peckish.wobble_scale - 1.5).abs() < 1e-9);
+    assert!((speed(content) - 1.0).abs() < 1e-9);
+    assert!((content.gait_cycle_px - BASE_GAIT_CYCLE_PX).abs() < 1e-9);
+  }
+
+  #[test]
+  fn satiated_character_ignores_the_pumpkin_unless_sulking() {
+    let settings = GaitSettings::default();
+    assert!(chase_gait(&settings, 99, false).is_some());
+    assert!(chase_gait(&settings, 100, false).is_none());
+
+    let sulking = chase_gait(&settings, 100, true).unwrap();
+    assert!((speed(sulking) - 0.6 * 0.75).abs() < 1e-9);
+
+    let always = GaitSettings {
+      ignore_at_or_above: None,
+      ..GaitSettings::default()
+    };
+    assert!(chase_gait(&always, 100, false).is_some());
+  }
+
+  #[test]
+  fn sulking_slows_the_chase() {
+    let settings = GaitSettings::default();
+    let normal = chase_gait(&settings, 30, false).unwrap();
+    let sulking = chase_gait(&settings, 30, true).unwrap();
+    assert!((speed(sulking) - speed(normal) * 0.75).abs() < 1e-9);
+    assert_eq!(sulking.wobble_scale, normal.wobble_scale);
+  }
+
+  #[test]
+  fn clamps_out_of_range_curves() {
+    let settings = GaitSettings {
+      curve: vec![GaitPoint {
+        pumpkin_index: 0,
+        speed: 50.0,
+        stride: 0.0,
+      }],
+      ignore_at_or_above: None,
+      sulking_speed_factor: 1.0,
+    };
+    let gait = chase_gait(&settings, 80, false).unwrap();
+    assert!((speed(gait) - 5.0).abs() < 1e-9);
+    assert!((gait.wobble_scale - 0.3).abs() < 1e-9);
+
+    let empty = GaitSettings {
+      curve: Vec::new(),
+      ..settings
+    };
+    let gait = chase_gait(&empty, 80, false).unwrap();
+    assert!((speed(gait) - 1.0).abs() < 1e-9);
+  }
+
+  #[test]
+  fn slowed_shortens_steps_down_to_the_minimum_speed() {
+    let gait = chase_gait(&GaitSettings::default(), 60, false).unwrap();
+    let sick = gait.slowed(0.5);
+    assert!((speed(sick) - 0.5).abs() < 1e-9);
+    assert_eq!(sick.gait_cycle_px, gait.gait_cycle_px);
+    assert_eq!(sick.wobble_scale, gait.wobble_scale);
+
+    assert!((speed(gait.slowed(0.0)) - 0.2).abs() < 1e-9);
+  }
+}
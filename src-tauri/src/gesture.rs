@@ -0,0 +1,313 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GestureSettings {
+  /// Clicks closer together than this belong to the same burst. A burst of
+  /// two is a double-click, longer bursts are a click streak.
+  pub click_gap_ms: u64,
+  /// Values below 3 count as 3 so a double-click never reads as a streak.
+  pub streak_clicks: usize,
+  /// Back-and-forth passes over the character, without pressing, that make a stroke.
+  pub stroke_passes: usize,
+  pub stroke_min_px: f64,
+  /// Faster passes are just the cursor flying by.
+  pub stroke_max_speed: f64,
+  /// Direction changes while dragging that make a shake.
+  pub shake_reversals: usize,
+  pub shake_window_ms: u64,
+  pub shake_min_px: f64,
+  /// Pixels per millisecond a drag swing needs to count as shaking.
+  pub shake_min_speed: f64,
+  /// Movement pauses longer than this start a new gesture.
+  pub idle_reset_ms: u64,
+  pub dizzy_secs: u64,
+}
+
+impl Default for GestureSettings {
+  fn default() -> Self {
+    Self {
+      click_gap_ms: 350,
+      streak_clicks: 3,
+      stroke_passes: 3,
+      stroke_min_px: 30.0,
+      stroke_max_speed: 1.2,
+      shake_reversals: 4,
+      shake_window_ms: 1200,
+      shake_min_px: 40.0,
+      shake_min_speed: 0.8,
+      idle_reset_ms: 600,
+      dizzy_secs: 4,
+    }
+  }
+}
+
+/// Raw input reported by the webview. Coordinates are screen pixels so a
+/// drag that moves the window still reads as motion.
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PointerSample {
+  Click { t: u64 },
+  Move { x: f64, t: u64, dragging: bool },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "gesture", rename_all = "snake_case")]
+pub enum Gesture {
+  DoubleClick,
+  ClickStreak { clicks: usize },
+  Stroke,
+  Shake,
+}
+
+/// One horizontal sweep between two direction changes.
+#[derive(Clone, Copy, Debug)]
+struct Swing {
+  direction: f64,
+  start_x: f64,
+  start_t: u64,
+  last_x: f64,
+  last_t: u64,
+}
+
+impl Swing {
+  fn length(&self) -> f64 {
+    (self.last_x - self.start_x).abs()
+  }
+
+  fn speed(&self) -> f64 {
+    self.length() / self.last_t.saturating_sub(self.start_t).max(1) as f64
+  }
+}
+
+/// Turns pointer samples into gestures. Time comes from the samples and from
+/// `flush`, never from the system clock.
+#[derive(Debug, Default)]
+pub struct GestureRecognizer {
+  clicks: Vec<u64>,
+  swing: Option<Swing>,
+  dragging: bool,
+  strokes: usize,
+  shake_reversals: Vec<u64>,
+}
+
+impl GestureRecognizer {
+  pub fn feed(&mut self, settings: &GestureSettings, sample: PointerSample) -> Option<Gesture> {
+    match sample {
+      PointerSample::Click { t } => {
+        let burst = self.flush(settings, t);
+        self.clicks.push(t);
+        burst
+      }
+      PointerSample::Move { x, t, dragging } => self.track_move(settings, x, t, dragging),
+    }
+  }
+
+  /// Resolves a click burst once no click has followed for `click_gap_ms`.
+  pub fn flush(&mut self, settings: &GestureSettings, now_ms: u64) -> Option<Gesture> {
+    let last = *self.clicks.last()?;
+    if now_ms.saturating_sub(last) <= settings.click_gap_ms {
+      return None;
+    }
+
+    let clicks = std::mem::take(&mut self.clicks).len();
+    if clicks >= settings.streak_clicks.max(3) {
+      Some(Gesture::ClickStreak { clicks })
+    } else if clicks == 2 {
+      Some(Gesture::DoubleClick)
+    } else {
+      None
+    }
+  }
+
+  fn reset_motion(&mut self) {
+    self.swing = None;
+    self.strokes = 0;
+    self.shake_reversals.clear();
+  }
+
+  fn track_move(&mut self, settings: &GestureSettings, x: f64, t: u64, dragging: bool) -> Option<Gesture> {
+    let stale = self
+      .swing
+      .is_some_and(|swing| t.saturating_sub(swing.last_t) > settings.idle_reset_ms);
+    if dragging != self.dragging || stale {
+      self.dragging = dragging;
+      self.reset_motion();
+    }
+
+    let Some(mut swing) = self.swing else {
+      self.swing = Some(Swing {
+        direction: 0.0,
+        start_x: x,
+        start_t: t,
+        last_x: x,
+        last_t: t,
+      });
+      return None;
+    };
+
+    let step = x - swing.last_x;
+    if step == 0.0 {
+      return None;
+    }
+    let direction = step.signum();
+    if swing.direction == 0.0 || direction == swing.direction {
+      swing.direction = direction;
+      swing.last_x = x;
+      swing.last_t = t;
+      self.swing = Some(swing);
+      return None;
+    }
+
+    // Direction changed: the previous swing is complete.
+    self.swing = Some(Swing {
+      direction,
+      start_x: swing.last_x,
+      start_t: swing.last_t,
+      last_x: x,
+      last_t: t,
+    });
+    if dragging {
+      self.shake_swing(settings, swing, t)
+    } else {
+      self.stroke_swing(settings, swing)
+    }
+  }
+
+  fn stroke_swing(&mut self, settings: &GestureSettings, swing: Swing) -> Option<Gesture> {
+    if swing.length() < settings.stroke_min_px {
+      return None;
+    }
+    if swing.speed() > settings.stroke_max_speed {
+      self.strokes = 0;
+      return None;
+    }
+
+    self.strokes += 1;
+    if self.strokes < settings.stroke_passes {
+      return None;
+    }
+    self.strokes = 0;
+    Some(Gesture::Stroke)
+  }
+
+  fn shake_swing(&mut self, settings: &GestureSettings, swing: Swing, now_ms: u64) -> Option<Gesture> {
+    if swing.length() < settings.shake_min_px || swing.speed() < settings.shake_min_speed {
+      return None;
+    }
+
+    self.shake_reversals.push(now_ms);
+    self
+      .shake_reversals
+      .retain(|at| now_ms.saturating_sub(*at) <= settings.shake_window_ms);
+    if self.shake_reversals.len() < settings.shake_reversals {
+      return None;
+    }
+    self.shake_reversals.clear();
+    Some(Gesture::Shake)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn clicks(times: &[u64]) -> Vec<PointerSample> {
+    times.iter().map(|&t| PointerSample::Click { t }).collect()
+  }
+
+  /// Alternates between `x = 0` and `x = 100`, one sample every `step_ms`.
+  fn sweeps(start_ms: u64, count: u64, step_ms: u64, dragging: bool) -> Vec<PointerSample> {
+    (0..count)
+      .map(|index| PointerSample::Move {
+        x: if index % 2 == 0 { 0.0 } else { 100.0 },
+        t: start_ms + index * step_ms,
+        dragging,
+      })
+      .collect()
+  }
+
+  fn recognize(settings: &GestureSettings, samples: &[PointerSample], flush_at: u64) -> Vec<Gesture> {
+    let mut recognizer = GestureRecognizer::default();
+    let mut found: Vec<Gesture> = samples
+      .iter()
+      .filter_map(|sample| recognizer.feed(settings, *sample))
+      .collect();
+    found.extend(recognizer.flush(settings, flush_at));
+    found
+  }
+
+  #[test]
+  fn click_bursts() {
+    let settings = GestureSettings::default();
+    let cases: [(&[u64], u64, &[Gesture]); 6] = [
+      (&[0], 1_000, &[]),
+      (&[0, 200], 1_000, &[Gesture::DoubleClick]),
+      (&[0, 200], 400, &[]),
+      (&[0, 100, 200, 300], 1_000, &[Gesture::ClickStreak { clicks: 4 }]),
+      (
+        &[0, 200, 1_000, 1_100],
+        2_000,
+        &[Gesture::DoubleClick, Gesture::DoubleClick],
+      ),
+      (&[0, 1_000], 2_000, &[]),
+    ];
+    for (times, flush_at, expected) in cases {
+      assert_eq!(
+        recognize(&settings, &clicks(times), flush_at),
+        expected,
+        "clicks at {:?}",
+        times
+      );
+    }
+  }
+
+  #[test]
+  fn short_streak_settings_keep_double_clicks() {
+    let settings = GestureSettings {
+      streak_clicks: 2,
+      ..GestureSettings::default()
+    };
+    assert_eq!(recognize(&settings, &clicks(&[0, 200]), 1_000), [Gesture::DoubleClick]);
+  }
+
+  #[test]
+  fn strokes_need_slow_passes() {
+    let settings = GestureSettings::default();
+    let cases = [
+      (sweeps(0, 5, 200, false), vec![Gesture::Stroke]),
+      (sweeps(0, 4, 200, false), vec![]),
+      (sweeps(0, 5, 50, false), vec![]),
+      (sweeps(0, 9, 200, false), vec![Gesture::Stroke, Gesture::Stroke]),
+    ];
+    for (samples, expected) in cases {
+      assert_eq!(recognize(&settings, &samples, 0), expected);
+    }
+  }
+
+  #[test]
+  fn shakes_need_fast_reversals_while_dragging() {
+    let settings = GestureSettings::default();
+    let cases = [
+      (sweeps(0, 6, 50, true), vec![Gesture::Shake]),
+      (sweeps(0, 5, 50, true), vec![]),
+      (sweeps(0, 6, 200, true), vec![]),
+    ];
+    for (samples, expected) in cases {
+      assert_eq!(recognize(&settings, &samples, 0), expected);
+    }
+  }
+
+  #[test]
+  fn pauses_and_drag_changes_start_over() {
+    let settings = GestureSettings::default();
+
+    let mut paused = sweeps(0, 4, 200, false);
+    paused.extend(sweeps(1_400, 4, 200, false));
+    assert_eq!(recognize(&settings, &paused, 0), []);
+
+    let mut switched = sweeps(0, 4, 200, false);
+    switched.extend(sweeps(800, 4, 200, true));
+    assert_eq!(recognize(&settings, &switched, 0), []);
+  }
+}
@@ -0,0 +1,336 @@
+use crate::path_planner::Rect;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HideSeekSettings {
+  pub rounds: u32,
+  pub time_limit_secs: u64,
+  /// How often the character calls out from its hiding spot.
+  pub hint_interval_secs: u64,
+  /// How much of the character stays on screen while hidden.
+  pub peek_px: i32,
+  /// Pause between rounds so the player sees the result.
+  pub round_break_ms: u64,
+}
+
+impl Default for HideSeekSettings {
+  fn default() -> Self {
+    Self {
+      rounds: 3,
+      time_limit_secs: 25,
+      hint_interval_secs: 6,
+      peek_px: 36,
+      round_break_ms: 1500,
+    }
+  }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HideSide {
+  Left,
+  Right,
+  Top,
+  Bottom,
+}
+
+impl HideSide {
+  const ALL: [HideSide; 4] = [HideSide::Left, HideSide::Right, HideSide::Top, HideSide::Bottom];
+
+  pub fn from_seed(seed: u64) -> Self {
+    Self::ALL[(seed % Self::ALL.len() as u64) as usize]
+  }
+
+  /// Top-left position that leaves `peek_px` of the window on screen along
+  /// this edge. `along` in `0.0..=1.0` picks the spot on the edge.
+  pub fn hiding_position(self, bounds: Rect, width: i32, height: i32, peek_px: i32, along: f64) -> (i32, i32) {
+    let along = along.clamp(0.0, 1.0);
+    let span_x = bounds.x + ((bounds.width - width).max(0) as f64 * along).round() as i32;
+    let span_y = bounds.y + ((bounds.height - height).max(0) as f64 * along).round() as i32;
+    match self {
+      HideSide::Left => (bounds.x - width + peek_px, span_y),
+      HideSide::Right => (bounds.right() - peek_px, span_y),
+      HideSide::Top => (span_x, bounds.y - height + peek_px),
+      HideSide::Bottom => (span_x, bounds.bottom() - peek_px),
+    }
+  }
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum HideSeekEvent {
+  RoundStarted {
+    round: u32,
+    rounds: u32,
+  },
+  Hint {
+    side: HideSide,
+  },
+  Found {
+    round: u32,
+    elapsed_ms: u64,
+  },
+  Missed {
+    round: u32,
+    side: HideSide,
+  },
+  Finished {
+    found: u32,
+    rounds: u32,
+    record: HideSeekRecord,
+  },
+}
+
+/// Persisted hide-and-seek results. A streak counts rounds found in a row,
+/// across games.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HideSeekRecord {
+  pub found: u32,
+  pub missed: u32,
+  pub current_streak: u32,
+  pub best_streak: u32,
+}
+
+impl HideSeekRecord {
+  pub fn record_round(&mut self, found: bool) {
+    if found {
+      self.found += 1;
+      self.current_streak += 1;
+      self.best_streak = self.best_streak.max(self.current_streak);
+    } else {
+      self.missed += 1;
+      self.current_streak = 0;
+    }
+  }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Phase {
+  Waiting,
+  Hiding {
+    side: HideSide,
+    started_ms: u64,
+    last_hint_ms: u64,
+  },
+  Done,
+}
+
+/// Round state machine: `Waiting -> Hiding -> (found | missed) -> Waiting`
+/// until every round has been played.
+#[derive(Debug)]
+pub struct HideSeekGame {
+  rounds: u32,
+  round: u32,
+  found: u32,
+  phase: Phase,
+}
+
+impl HideSeekGame {
+  pub fn new(settings: &HideSeekSettings) -> Self {
+    Self {
+      rounds: settings.rounds.max(1),
+      round: 0,
+      found: 0,
+      phase: Phase::Waiting,
+    }
+  }
+
+  pub fn is_hiding(&self) -> bool {
+    matches!(self.phase, Phase::Hiding { .. })
+  }
+
+  pub fn found_count(&self) -> u32 {
+    self.found
+  }
+
+  pub fn rounds(&self) -> u32 {
+    self.rounds
+  }
+
+  /// Starts the next round, or returns `None` once all rounds are played.
+  pub fn start_round(&mut self, side: HideSide, now_ms: u64) -> Option<HideSeekEvent> {
+    if self.phase != Phase::Waiting {
+      return None;
+    }
+    if self.round >= self.rounds {
+      self.phase = Phase::Done;
+      return None;
+    }
+
+    self.round += 1;
+    self.phase = Phase::Hiding {
+      side,
+      started_ms: now_ms,
+      last_hint_ms: now_ms,
+    };
+    Some(HideSeekEvent::RoundStarted {
+      round: self.round,
+      rounds: self.rounds,
+    })
+  }
+
+  /// Emits hints while hiding and ends the round when time runs out.
+  pub fn tick(&mut self, settings: &HideSeekSettings, now_ms: u64) -> Option<HideSeekEvent> {
+    let Phase::Hiding {
+      side,
+      started_ms,
+      last_hint_ms,
+    } = self.phase
+    else {
+      return None;
+    };
+
+    if now_ms.saturating_sub(started_ms) >= settings.time_limit_secs.saturating_mul(1000) {
+      self.phase = Phase::Waiting;
+      return Some(HideSeekEvent::Missed {
+        round: self.round,
+        side,
+      });
+    }
+
+    let hint_interval_ms = settings.hint_interval_secs.saturating_mul(1000);
+    if hint_interval_ms > 0 && now_ms.saturating_sub(last_hint_ms) >= hint_interval_ms {
+      self.phase = Phase::Hiding {
+        side,
+        started_ms,
+        last_hint_ms: now_ms,
+      };
+      return Some(HideSeekEvent::Hint { side });
+    }
+
+    None
+  }
+
+  /// The player clicked the character.
+  pub fn found(&mut self, now_ms: u64) -> Option<HideSeekEvent> {
+    let Phase::Hiding { started_ms, .. } = self.phase else {
+      return None;
+    };
+
+    self.phase = Phase::Waiting;
+    self.found += 1;
+    Some(HideSeekEvent::Found {
+      round: self.round,
+      elapsed_ms: now_ms.saturating_sub(started_ms),
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn settings() -> HideSeekSettings {
+    HideSeekSettings {
+      rounds: 2,
+      time_limit_secs: 10,
+      hint_interval_secs: 3,
+      ..HideSeekSettings::default()
+    }
+  }
+
+  #[test]
+  fn hints_repeat_until_the_round_times_out() {
+    let settings = settings();
+    let mut game = HideSeekGame::new(&settings);
+    assert!(matches!(
+      game.start_round(HideSide::Left, 1_000),
+      Some(HideSeekEvent::RoundStarted { round: 1, rounds: 2 })
+    ));
+    assert!(game.is_hiding());
+
+    let events: Vec<(u64, HideSeekEvent)> = (1_500..=11_000)
+      .step_by(500)
+      .filter_map(|now_ms| game.tick(&settings, now_ms).map(|event| (now_ms, event)))
+      .collect();
+    let hints: Vec<u64> = events
+      .iter()
+      .filter(|(_, event)| matches!(event, HideSeekEvent::Hint { side: HideSide::Left }))
+      .map(|(now_ms, _)| *now_ms)
+      .collect();
+    assert_eq!(hints, vec![4_000, 7_000, 10_000]);
+    assert!(matches!(
+      events.last(),
+      Some((
+        11_000,
+        HideSeekEvent::Missed {
+          round: 1,
+          side: HideSide::Left
+        }
+      ))
+    ));
+    assert!(!game.is_hiding());
+    assert!(game.tick(&settings, 20_000).is_none());
+  }
+
+  #[test]
+  fn finding_ends_the_round_and_the_game_stops_after_the_last_one() {
+    let settings = settings();
+    let mut game = HideSeekGame::new(&settings);
+    assert!(game.found(0).is_none());
+
+    game.start_round(HideSide::Top, 0).unwrap();
+    assert!(game.start_round(HideSide::Top, 100).is_none());
+    assert!(matches!(
+      game.found(2_500),
+      Some(HideSeekEvent::Found {
+        round: 1,
+        elapsed_ms: 2_500
+      })
+    ));
+    assert!(game.found(2_600).is_none());
+
+    game.start_round(HideSide::Bottom, 5_000).unwrap();
+    assert!(matches!(game.found(6_000), Some(HideSeekEvent::Found { round: 2, .. })));
+    assert!(game.start_round(HideSide::Left, 7_000).is_none());
+    assert_eq!((game.found_count(), game.rounds()), (2, 2));
+  }
+
+  #[test]
+  fn zero_rounds_still_plays_one() {
+    let game = HideSeekGame::new(&HideSeekSettings {
+      rounds: 0,
+      ..HideSeekSettings::default()
+    });
+    assert_eq!(game.rounds(), 1);
+  }
+
+  #[test]
+  fn hiding_positions_leave_the_peek_on_screen() {
+    let bounds = Rect::new(0, 0, 1920, 1080);
+    assert_eq!(HideSide::Left.hiding_position(bounds, 300, 300, 36, 0.5), (-264, 390));
+    assert_eq!(HideSide::Right.hiding_position(bounds, 300, 300, 36, 0.0), (1884, 0));
+    assert_eq!(HideSide::Top.hiding_position(bounds, 300, 300, 36, 1.0), (1620, -264));
+    assert_eq!(
+      HideSide::Bottom.hiding_position(bounds, 300, 300, 36, 2.0),
+      (1620, 1044)
+    );
+  }
+
+  #[test]
+  fn sides_cycle_through_the_seed() {
+    let sides: Vec<HideSide> = (0..5).map(HideSide::from_seed).collect();
+    assert_eq!(
+      sides,
+      [
+        HideSide::Left,
+        HideSide::Right,
+        HideSide::Top,
+        HideSide::Bottom,
+        HideSide::Left
+      ]
+    );
+  }
+
+  #[test]
+  fn streaks_carry_across_games_and_reset_on_a_miss() {
+    let mut record = HideSeekRecord::default();
+    for found in [true, true, true, false, true] {
+      record.record_round(found);
+    }
+    assert_eq!((record.found, record.missed), (4, 1));
+    assert_eq!((record.current_streak, record.best_streak), (1, 3));
+  }
+}
@@ -0,0 +1,107 @@
+//! System-wide idle and lock detection. Only Windows reports anything; other
+//! platforms always look present.
+
+#[cfg(windows)]
+mod platform {
+  use std::{ffi::c_void, ptr};
+
+  #[repr(C)]
+  struct LastInputInfo {
+    cb_size: u32,
+    time: u32,
+  }
+
+  const WTS_CURRENT_SESSION: u32 = u32::MAX;
+  /// `WTS_INFO_CLASS::WTSSessionInfoEx`.
+  const WTS_SESSION_INFO_EX: i32 = 25;
+  const WTS_SESSIONSTATE_LOCK: i32 = 0;
+  /// `WTSINFOEXW` is a `u32` level followed by an 8-byte aligned union whose
+  /// level 1 member starts with `SessionId` and `SessionState`.
+  const SESSION_FLAGS_OFFSET: usize = 16;
+
+  #[link(name = "user32")]
+  extern "system" {
+    fn GetLastInputInfo(info: *mut LastInputInfo) -> i32;
+  }
+
+  #[link(name = "kernel32")]
+  extern "system" {
+    fn GetTickCount() -> u32;
+  }
+
+  #[link(name = "wtsapi32")]
+  extern "system" {
+    fn WTSQuerySessionInformationW(
+      server: *mut c_void,
+      session_id: u32,
+      info_class: i32,
+      buffer: *mut *mut c_void,
+      bytes: *mut u32,
+    ) -> i32;
+    fn WTSFreeMemory(memory: *mut c_void);
+  }
+
+  pub fn idle_ms() -> Option<u64> {
+    let mut info = LastInputInfo {
+      cb_size: std::mem::size_of::<LastInputInfo>() as u32,
+      time: 0,
+    };
+    // SAFETY: `info` is a properly sized LASTINPUTINFO that outlives the call.
+    if unsafe { GetLastInputInfo(&mut info) } == 0 {
+      return None;
+    }
+    // SAFETY: no arguments; both tick counts wrap together every ~49 days.
+    let now = unsafe { GetTickCount() };
+    Some(now.wrapping_sub(info.time) as u64)
+  }
+
+  /// Reads the lock flag of the current session. Unlike probing the input
+  /// desktop, a UAC prompt does not count as locked. Windows 7 reports the
+  /// flag inverted; the app does not support it.
+  pub fn session_locked() -> bool {
+    let mut buffer: *mut c_void = ptr::null_mut();
+    let mut bytes = 0u32;
+    // SAFETY: a null server handle means the local machine; on success the
+    // API allocates `buffer`, which is released with WTSFreeMemory below.
+    let queried = unsafe {
+      WTSQuerySessionInformationW(
+        ptr::null_mut(),
+        WTS_CURRENT_SESSION,
+        WTS_SESSION_INFO_EX,
+        &mut buffer,
+        &mut bytes,
+      )
+    };
+    if queried == 0 || buffer.is_null() {
+      return false;
+    }
+
+    let flags = (bytes as usize >= SESSION_FLAGS_OFFSET + 4).then(|| {
+      // SAFETY: the buffer holds `bytes` bytes, enough to cover the field.
+      unsafe { ptr::read_unaligned(buffer.cast::<u8>().add(SESSION_FLAGS_OFFSET).cast::<i32>()) }
+    });
+    // SAFETY: `buffer` came from WTSQuerySessionInformationW and is not used again.
+    unsafe { WTSFreeMemory(buffer) };
+    flags == Some(WTS_SESSIONSTATE_LOCK)
+  }
+}
+
+#[cfg(not(windows))]
+mod platform {
+  pub fn idle_ms() -> Option<u64> {
+    None
+  }
+
+  pub fn session_locked() -> bool {
+    false
+  }
+}
+
+/// Milliseconds since the last mouse or keyboard input anywhere on the system.
+pub fn system_idle_ms() -> Option<u64> {
+  platform::idle_ms()
+}
+
+pub fn session_locked() -> bool {
+  platform::session_locked()
+}
@@ -0,0 +1,74 @@
+use crate::sickness::SicknessTransition;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+/// Oldest entries are dropped past this many.
+const JOURNAL_LIMIT: usize = 200;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum JournalEvent {
+  Sickness(SicknessTransition),
+  BondLevelUp { level: u32, name: String },
+}
+
+/// One line of the character's history, kept in the save file.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct JournalEntry {
+  /// Local time as `YYYY-MM-DD HH:MM:SS`.
+  pub at: String,
+  #[serde(flatten)]
+  pub event: JournalEvent,
+}
+
+pub fn append(journal: &mut Vec<JournalEntry>, at: NaiveDateTime, event: JournalEvent) {
+  journal.push(JournalEntry {
+    at: at.format("%Y-%m-%d %H:%M:%S").to_string(),
+    event,
+  });
+  if journal.len() > JOURNAL_LIMIT {
+    let excess = journal.len() - JOURNAL_LIMIT;
+    journal.drain(..excess);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn at(second: u32) -> NaiveDateTime {
+    chrono::NaiveDate::from_ymd_opt(2026, 3, 2)
+      .and_then(|date| date.and_hms_opt(12, second / 60, second % 60))
+      .unwrap()
+  }
+
+  #[test]
+  fn keeps_only_the_newest_entries() {
+    let mut journal = Vec::new();
+    for meals in 0..(JOURNAL_LIMIT as u32 + 5) {
+      let event = JournalEvent::Sickness(SicknessTransition::Recovering { meals, needed: 500 });
+      append(&mut journal, at(meals), event);
+    }
+
+    assert_eq!(journal.len(), JOURNAL_LIMIT);
+    assert!(matches!(
+      journal[0].event,
+      JournalEvent::Sickness(SicknessTransition::Recovering { meals: 5, .. })
+    ));
+    assert_eq!(journal.last().unwrap().at, "2026-03-02 12:03:24");
+  }
+
+  #[test]
+  fn entries_flatten_the_event_into_one_object() {
+    let mut journal = Vec::new();
+    append(
+      &mut journal,
+      at(0),
+      JournalEvent::Sickness(SicknessTransition::Recovered),
+    );
+    assert_eq!(
+      serde_json::to_value(&journal[0]).unwrap(),
+      serde_json::json!({ "at": "2026-03-02 12:00:00", "kind": "sickness", "transition": "recovered" })
+    );
+  }
+}
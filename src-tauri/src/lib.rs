@@ -1,18 +1,195 @@
+mod assets;
+mod audio;
+mod behavior;
+mod bond;
+mod carry;
+mod catch_game;
+mod character;
+mod clock;
+mod cursor;
+mod decay;
+mod feeder;
+mod flee;
+mod food;
+mod gait;
+mod gesture;
+mod hide_seek;
+mod idle;
+mod journal;
+mod meal;
+mod mood;
+mod motion;
+mod pack_import;
+mod path_planner;
+mod pumpkin_life;
+mod rng;
+mod save;
+mod scripting;
+mod settings;
+mod sickness;
+mod sleep;
+mod throw;
+mod voice_select;
+mod wav;
+
+use assets::AssetEntry;
+use audio::{AudioCategory, Mixer, NullBackend, PlayOutcome, RodioBackend, VoiceRequest};
+use behavior::{Blackboard, Plan};
+use bond::{BondRecord, BondStatus, Care};
+use catch_game::{CatchInput, CatchSim};
+use character::{CharacterManifest, PackSource, PackSummary};
+use cursor::{CursorEvent, CursorMode, CursorSettings, CursorWalker, Facing};
+use flee::{FleeOutcome, FleeRecord, Threat};
+use food::{FoodItem, MoodEffect};
+use gait::Gait;
+use gesture::{Gesture, GestureRecognizer, PointerSample};
+use hide_seek::{HideSeekEvent, HideSeekGame, HideSide};
+use meal::MealProgress;
+use mood::{Mood, MoodEngine, MoodTrigger};
+use motion::{Attachment, MotionOwner, MotionScheduler};
+use pack_import::ImportReport;
+use path_planner::{touching_or_overlapping, Rect};
+use pumpkin_life::PumpkinStage;
+use rng::Dice;
+use scripting::{ScriptCommand, ScriptEvent, ScriptHost};
 use serde::Serialize;
+use sickness::{SicknessState, SicknessTransition};
+use sleep::{SleepReason, SleepSettings};
 use std::{
-  sync::{Mutex, OnceLock},
+  collections::HashMap,
+  path::{Path, PathBuf},
+  sync::{mpsc, Arc, Mutex, OnceLock},
   thread,
-  time::{Duration, SystemTime, UNIX_EPOCH},
+  time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
-use tauri::{Manager, PhysicalPosition, WebviewUrl, WebviewWindowBuilder, Window};
+use tauri::{Manager, PhysicalPosition, PhysicalSize, WebviewUrl, WebviewWindowBuilder, Window};
+use throw::{ThrowBody, ThrowEvent, VelocityTracker};
+use voice_select::VoiceSelector;
 
 static PUMPKIN_DRAGGING: OnceLock<Mutex<bool>> = OnceLock::new();
 static FOLLOW_PHASE: OnceLock<Mutex<u8>> = OnceLock::new();
 static FOLLOW_WORKER_RUNNING: OnceLock<Mutex<bool>> = OnceLock::new();
-static PUMPKIN_EATEN_PENDING: OnceLock<Mutex<bool>> = OnceLock::new();
+static PUMPKIN_BITES_PENDING: OnceLock<Mutex<Vec<PumpkinBite>>> = OnceLock::new();
+static MEAL_PROGRESS: OnceLock<Mutex<MealProgress>> = OnceLock::new();
+static MEAL_WORKER_RUNNING: OnceLock<Mutex<bool>> = OnceLock::new();
 static PUMPKIN_TIMEOUT_PENDING: OnceLock<Mutex<bool>> = OnceLock::new();
 static PUMPKIN_SESSION_ID: OnceLock<Mutex<u64>> = OnceLock::new();
 static PUMPKIN_CHASE_TIMED_OUT: OnceLock<Mutex<bool>> = OnceLock::new();
+static CHARACTER_SULKING: OnceLock<Mutex<bool>> = OnceLock::new();
+static MOOD: OnceLock<Mutex<MoodEngine>> = OnceLock::new();
+static LEGACY_HUNGER_IMPORT: OnceLock<Mutex<bool>> = OnceLock::new();
+static SLEEP: OnceLock<Mutex<SleepState>> = OnceLock::new();
+static SICKNESS: OnceLock<Mutex<SicknessState>> = OnceLock::new();
+static BOND: OnceLock<Mutex<BondRecord>> = OnceLock::new();
+static GESTURES: OnceLock<Mutex<GestureState>> = OnceLock::new();
+static THROW: OnceLock<Mutex<ThrowState>> = OnceLock::new();
+static APP_EPOCH: OnceLock<Instant> = OnceLock::new();
+static CURSOR_EVENTS: OnceLock<Mutex<Vec<CursorEvent>>> = OnceLock::new();
+static SCRIPT_EVENTS: OnceLock<Mutex<Vec<ScriptEvent>>> = OnceLock::new();
+static SCRIPT_COMMANDS: OnceLock<Mutex<Vec<ScriptCommand>>> = OnceLock::new();
+static PUMPKIN_SPAWNED_AT: OnceLock<Mutex<Option<Instant>>> = OnceLock::new();
+static PUMPKIN_STAGE_PENDING: OnceLock<Mutex<Option<PumpkinStage>>> = OnceLock::new();
+static CURRENT_FOOD: OnceLock<Mutex<Option<FoodItem>>> = OnceLock::new();
+static AUTO_FEED_PENDING: OnceLock<Mutex<bool>> = OnceLock::new();
+static PUMPKIN_REACHED: OnceLock<Mutex<bool>> = OnceLock::new();
+static PUMPKIN_STASHED_PENDING: OnceLock<Mutex<Option<u32>>> = OnceLock::new();
+static MOTION: OnceLock<Mutex<MotionScheduler>> = OnceLock::new();
+static FLEE_RESULT_PENDING: OnceLock<Mutex<Option<FleeResult>>> = OnceLock::new();
+static CATCH_INPUT: OnceLock<Mutex<CatchInput>> = OnceLock::new();
+static CATCH_UPDATE_PENDING: OnceLock<Mutex<Option<CatchUpdate>>> = OnceLock::new();
+static HIDE_SEEK: OnceLock<Mutex<Option<HideSeekSession>>> = OnceLock::new();
+static HIDE_SEEK_EVENTS_PENDING: OnceLock<Mutex<Vec<HideSeekEvent>>> = OnceLock::new();
+static AUDIO: OnceLock<Mutex<Option<mpsc::Sender<AudioMessage>>>> = OnceLock::new();
+static VOICE_CLIPS: OnceLock<Mutex<HashMap<String, Arc<[u8]>>>> = OnceLock::new();
+static VOICE_SELECTOR: OnceLock<Mutex<(VoiceSelector, Dice)>> = OnceLock::new();
+
+const PUMPKIN_FADE_OUT_MS: u64 = 600;
+/// Must match `identifier` in `tauri.conf.json`.
+const APP_IDENTIFIER: &str = "com.cuayo.widget";
+
+#[derive(Clone, Copy)]
+struct CharacterState {
+  pumpkin_index: u32,
+  sulking: bool,
+}
+
+#[derive(Clone, Serialize)]
+struct FleeResult {
+  #[serde(flatten)]
+  outcome: FleeOutcome,
+  record: FleeRecord,
+}
+
+/// Catch game progress since the frontend last asked.
+#[derive(Clone, Default, Serialize)]
+struct CatchUpdate {
+  caught: u32,
+  missed: u32,
+  nutrition: u32,
+  score: u32,
+  finished: bool,
+}
+
+struct HideSeekSession {
+  game: HideSeekGame,
+  started_at: Instant,
+}
+
+impl HideSeekSession {
+  fn now_ms(&self) -> u64 {
+    self.started_at.elapsed().as_millis() as u64
+  }
+}
+
+struct SleepState {
+  last_interaction: Instant,
+  asleep: Option<SleepReason>,
+  nap_until: Option<Instant>,
+  /// The user is away from the computer (see `DecaySettings::is_away`).
+  away: bool,
+}
+
+#[derive(Default)]
+struct ThrowState {
+  /// Where the frontend put the window during the current drag.
+  tracker: VelocityTracker,
+  events: Vec<ThrowEvent>,
+}
+
+#[derive(Default)]
+struct GestureState {
+  recognizer: GestureRecognizer,
+  dizzy_until: Option<Instant>,
+}
+
+#[derive(Clone, Serialize)]
+struct MoodSnapshot {
+  pumpkin_index: u32,
+  affection: u32,
+  energy: u32,
+  fun: u32,
+  mood: Mood,
+  asleep: Option<SleepReason>,
+  sick: bool,
+  away: bool,
+  dizzy: bool,
+  satisfied: bool,
+  expression: String,
+  voices: Vec<String>,
+  bond: BondStatus,
+}
+
+#[derive(Clone, Serialize)]
+struct PumpkinBite {
+  food: String,
+  nutrition: u32,
+  bites_taken: u32,
+  remaining_portion: f64,
+  finished: bool,
+  stage: PumpkinStage,
+  mood_effect: MoodEffect,
+  voice: Option<String>,
+}
 
 fn pumpkin_dragging_state() -> &'static Mutex<bool> {
   PUMPKIN_DRAGGING.get_or_init(|| Mutex::new(false))
@@ -26,8 +203,16 @@ fn follow_worker_running_state() -> &'static Mutex<bool> {
   FOLLOW_WORKER_RUNNING.get_or_init(|| Mutex::new(false))
 }
 
-fn pumpkin_eaten_pending_state() -> &'static Mutex<bool> {
-  PUMPKIN_EATEN_PENDING.get_or_init(|| Mutex::new(false))
+fn pumpkin_bites_pending_state() -> &'static Mutex<Vec<PumpkinBite>> {
+  PUMPKIN_BITES_PENDING.get_or_init(|| Mutex::new(Vec::new()))
+}
+
+fn meal_progress_state() -> &'static Mutex<MealProgress> {
+  MEAL_PROGRESS.get_or_init(|| Mutex::new(MealProgress::new(1)))
+}
+
+fn meal_worker_running_state() -> &'static Mutex<bool> {
+  MEAL_WORKER_RUNNING.get_or_init(|| Mutex::new(false))
 }
 
 fn pumpkin_timeout_pending_state() -> &'static Mutex<bool> {
@@ -42,6 +227,412 @@ fn pumpkin_chase_timed_out_state() -> &'static Mutex<bool> {
   PUMPKIN_CHASE_TIMED_OUT.get_or_init(|| Mutex::new(false))
 }
 
+fn pumpkin_spawned_at_state() -> &'static Mutex<Option<Instant>> {
+  PUMPKIN_SPAWNED_AT.get_or_init(|| Mutex::new(None))
+}
+
+fn pumpkin_stage_pending_state() -> &'static Mutex<Option<PumpkinStage>> {
+  PUMPKIN_STAGE_PENDING.get_or_init(|| Mutex::new(None))
+}
+
+fn current_food_state() -> &'static Mutex<Option<FoodItem>> {
+  CURRENT_FOOD.get_or_init(|| Mutex::new(None))
+}
+
+fn current_food() -> Option<FoodItem> {
+  current_food_state().lock().ok().and_then(|food| food.clone())
+}
+
+fn auto_feed_pending_state() -> &'static Mutex<bool> {
+  AUTO_FEED_PENDING.get_or_init(|| Mutex::new(false))
+}
+
+fn pumpkin_reached_state() -> &'static Mutex<bool> {
+  PUMPKIN_REACHED.get_or_init(|| Mutex::new(false))
+}
+
+fn pumpkin_stashed_pending_state() -> &'static Mutex<Option<u32>> {
+  PUMPKIN_STASHED_PENDING.get_or_init(|| Mutex::new(None))
+}
+
+fn flee_result_pending_state() -> &'static Mutex<Option<FleeResult>> {
+  FLEE_RESULT_PENDING.get_or_init(|| Mutex::new(None))
+}
+
+fn catch_input_state() -> &'static Mutex<CatchInput> {
+  CATCH_INPUT.get_or_init(|| Mutex::new(CatchInput::default()))
+}
+
+fn catch_update_pending_state() -> &'static Mutex<Option<CatchUpdate>> {
+  CATCH_UPDATE_PENDING.get_or_init(|| Mutex::new(None))
+}
+
+fn hide_seek_state() -> &'static Mutex<Option<HideSeekSession>> {
+  HIDE_SEEK.get_or_init(|| Mutex::new(None))
+}
+
+fn hide_seek_events_pending_state() -> &'static Mutex<Vec<HideSeekEvent>> {
+  HIDE_SEEK_EVENTS_PENDING.get_or_init(|| Mutex::new(Vec::new()))
+}
+
+fn motion_state() -> &'static Mutex<MotionScheduler> {
+  MOTION.get_or_init(|| Mutex::new(MotionScheduler::default()))
+}
+
+fn acquire_motion(owner: MotionOwner) -> Option<u64> {
+  motion_state().lock().ok().and_then(|mut motion| motion.acquire(owner))
+}
+
+fn release_motion(lease_id: u64) {
+  if let Ok(mut motion) = motion_state().lock() {
+    motion.release(lease_id);
+  }
+}
+
+fn holds_motion(lease_id: u64) -> bool {
+  motion_state()
+    .lock()
+    .map(|motion| motion.holds(lease_id))
+    .unwrap_or(false)
+}
+
+/// Moves the character and every window attached to it. Returns `false` once
+/// `lease_id` no longer owns the character's movement.
+fn move_character<R: tauri::Runtime>(
+  app: &tauri::AppHandle<R>,
+  lease_id: u64,
+  x: i32,
+  y: i32,
+) -> Result<bool, String> {
+  let attached = {
+    let Ok(motion) = motion_state().lock() else {
+      return Ok(false);
+    };
+    if !motion.holds(lease_id) {
+      return Ok(false);
+    }
+    motion.attachment_positions(x, y)
+  };
+
+  let Some(main_window) = app.get_webview_window("main") else {
+    return Ok(false);
+  };
+  main_window
+    .set_position(PhysicalPosition::new(x, y))
+    .map_err(|error| error.to_string())?;
+
+  for (label, attached_x, attached_y) in attached {
+    if let Some(attached_window) = app.get_webview_window(&label) {
+      let _ = attached_window.set_position(PhysicalPosition::new(attached_x, attached_y));
+    }
+  }
+
+  Ok(true)
+}
+
+fn set_pumpkin_reached(is_reached: bool) {
+  if let Ok(mut reached) = pumpkin_reached_state().lock() {
+    *reached = is_reached;
+  }
+}
+
+fn is_pumpkin_reached() -> bool {
+  if let Ok(reached) = pumpkin_reached_state().lock() {
+    return *reached;
+  }
+  false
+}
+
+fn character_sulking_state() -> &'static Mutex<bool> {
+  CHARACTER_SULKING.get_or_init(|| Mutex::new(false))
+}
+
+fn mood_state() -> &'static Mutex<MoodEngine> {
+  MOOD.get_or_init(|| Mutex::new(MoodEngine::default()))
+}
+
+fn legacy_hunger_import_state() -> &'static Mutex<bool> {
+  LEGACY_HUNGER_IMPORT.get_or_init(|| Mutex::new(false))
+}
+
+fn current_mood_engine() -> MoodEngine {
+  mood_state().lock().map(|engine| engine.clone()).unwrap_or_default()
+}
+
+fn sleep_state() -> &'static Mutex<SleepState> {
+  SLEEP.get_or_init(|| {
+    Mutex::new(SleepState {
+      last_interaction: Instant::now(),
+      asleep: None,
+      nap_until: None,
+      away: false,
+    })
+  })
+}
+
+fn current_sleep() -> Option<SleepReason> {
+  sleep_state().lock().ok().and_then(|state| state.asleep)
+}
+
+fn is_user_away() -> bool {
+  sleep_state().lock().map(|state| state.away).unwrap_or(false)
+}
+
+fn set_user_away(away: bool) {
+  if let Ok(mut state) = sleep_state().lock() {
+    state.away = away;
+  }
+}
+
+/// Resets the idle timer and wakes the character up.
+fn note_interaction() {
+  if let Ok(mut state) = sleep_state().lock() {
+    state.last_interaction = Instant::now();
+    state.asleep = None;
+    state.nap_until = None;
+  }
+}
+
+/// Falls asleep or stays awake based on how long nobody interacted. Anything
+/// holding a motion lease (a chase, a carry, a game) counts as interaction.
+fn update_sleep(sleep_settings: &SleepSettings, minute_of_day: u32) -> Option<SleepReason> {
+  if !motion_state().lock().map(|motion| motion.is_free()).unwrap_or(true) {
+    note_interaction();
+  }
+
+  let mut state = sleep_state().lock().ok()?;
+  let idle_ms = state.last_interaction.elapsed().as_millis() as u64;
+  let napping = state.nap_until.is_some_and(|until| Instant::now() < until);
+  let asleep = sleep_settings
+    .should_sleep(idle_ms, minute_of_day)
+    .or_else(|| napping.then_some(SleepReason::Nap));
+  if asleep.is_some() != state.asleep.is_some() {
+    log::info!("character {}", if asleep.is_some() { "fell asleep" } else { "woke up" });
+  }
+  state.asleep = asleep;
+  asleep
+}
+
+fn sickness_state() -> &'static Mutex<SicknessState> {
+  SICKNESS.get_or_init(|| Mutex::new(SicknessState::default()))
+}
+
+fn is_sick() -> bool {
+  sickness_state().lock().map(|state| state.sick).unwrap_or(false)
+}
+
+/// Journals a sickness transition and saves it right away instead of waiting
+/// for the next periodic save. `update_save` replaces the file atomically, so a
+/// crash loses at most the entry being written, never the earlier journal.
+fn record_sickness_transition(transition: SicknessTransition) {
+  log::info!("sickness: {:?}", transition);
+  let sickness = sickness_state().lock().map(|state| *state).unwrap_or_default();
+  let now = chrono::Local::now().naive_local();
+  let result = save::update_save(|save| {
+    save.sickness = sickness;
+    journal::append(&mut save.journal, now, journal::JournalEvent::Sickness(transition));
+  });
+  if let Err(error) = result {
+    log::warn!("failed to record sickness: {}", error);
+  }
+}
+
+fn bond_state() -> &'static Mutex<BondRecord> {
+  BOND.get_or_init(|| Mutex::new(BondRecord::default()))
+}
+
+fn current_bond_status() -> BondStatus {
+  let bond_settings = settings::current_settings().bond;
+  bond_state()
+    .lock()
+    .map(|bond| bond.status(&bond_settings))
+    .unwrap_or_else(|_| BondRecord::default().status(&bond_settings))
+}
+
+/// Credits one act of care to the bond. Level-ups are journaled and saved
+/// right away; everything else rides along with the periodic save.
+fn record_care(care: Care) {
+  let bond_settings = settings::current_settings().bond;
+  let now = chrono::Local::now().naive_local();
+  let Some(level) = bond_state()
+    .lock()
+    .ok()
+    .and_then(|mut bond| bond.care(&bond_settings, now, care))
+  else {
+    return;
+  };
+
+  let name = bond_settings
+    .levels
+    .get(level as usize)
+    .map(|level| level.name.clone())
+    .unwrap_or_default();
+  log::info!("bond reached level {} ({})", level, name);
+  let bond = bond_state().lock().map(|bond| bond.clone()).unwrap_or_default();
+  let result = save::update_save(|save| {
+    save.bond = bond;
+    journal::append(&mut save.journal, now, journal::JournalEvent::BondLevelUp { level, name });
+  });
+  if let Err(error) = result {
+    log::warn!("failed to record bond level: {}", error);
+  }
+}
+
+fn gesture_state() -> &'static Mutex<GestureState> {
+  GESTURES.get_or_init(|| Mutex::new(GestureState::default()))
+}
+
+fn is_dizzy() -> bool {
+  gesture_state()
+    .lock()
+    .ok()
+    .and_then(|state| state.dizzy_until)
+    .is_some_and(|until| Instant::now() < until)
+}
+
+/// Applies what a recognized gesture does to the character.
+fn react_to_gesture(gesture: Gesture) {
+  note_interaction();
+  let trigger = match gesture {
+    Gesture::DoubleClick => MoodTrigger::DoubleClick,
+    Gesture::ClickStreak { .. } => MoodTrigger::ClickStreak,
+    Gesture::Stroke => {
+      record_care(Care::Pet);
+      MoodTrigger::Stroke
+    }
+    Gesture::Shake => {
+      let dizzy_secs = settings::current_settings().gesture.dizzy_secs;
+      if let Ok(mut state) = gesture_state().lock() {
+        state.dizzy_until = Some(Instant::now() + Duration::from_secs(dizzy_secs));
+      }
+      MoodTrigger::Shake
+    }
+  };
+  apply_mood(trigger);
+}
+
+fn apply_mood(trigger: MoodTrigger) {
+  let mood_settings = settings::current_settings().mood;
+  if let Ok(mut engine) = mood_state().lock() {
+    engine.apply(&mood_settings, trigger);
+  }
+}
+
+fn mood_snapshot() -> MoodSnapshot {
+  let engine = current_mood_engine();
+  let mood = engine.mood();
+  let current_settings = settings::current_settings();
+  let mut profile = current_settings.mood.profile(mood);
+  let asleep = current_sleep();
+  let sick = is_sick();
+  if sick {
+    profile.expression = current_settings.sickness.expression.clone();
+    profile.voices = vec![current_settings.sickness.voice.clone()];
+  }
+  if asleep.is_some() {
+    profile.expression = current_settings.sleep.expression;
+    profile.voices.clear();
+  }
+  MoodSnapshot {
+    pumpkin_index: engine.pumpkin_index(),
+    affection: engine.needs.affection.round() as u32,
+    energy: engine.needs.energy.round() as u32,
+    fun: engine.needs.fun.round() as u32,
+    mood,
+    asleep,
+    sick,
+    away: is_user_away(),
+    dizzy: is_dizzy(),
+    satisfied: mood.is_satisfied() && asleep.is_none() && !sick,
+    expression: profile.expression,
+    voices: profile.voices,
+    bond: current_bond_status(),
+  }
+}
+
+fn persist_progress() {
+  let needs = current_mood_engine().needs;
+  let sickness = sickness_state().lock().map(|state| *state).unwrap_or_default();
+  let bond = bond_state().lock().map(|bond| bond.clone()).unwrap_or_default();
+  let result = save::update_save(|save| {
+    save.needs = Some(needs);
+    save.sickness = sickness;
+    save.bond = bond;
+  });
+  if let Err(error) = result {
+    log::warn!("failed to write progress: {}", error);
+  }
+}
+
+/// Drifts the needs once a second and writes them to the save file every
+/// `PERSIST_EVERY` ticks.
+fn start_mood_worker() {
+  const TICK: Duration = Duration::from_secs(1);
+  const PERSIST_EVERY: u32 = 15;
+
+  thread::spawn(move || {
+    let mut last_tick = Instant::now();
+    let mut ticks = 0u32;
+    loop {
+      thread::sleep(TICK);
+      let elapsed_ms = last_tick.elapsed().as_millis() as u64;
+      last_tick = Instant::now();
+
+      let current_settings = settings::current_settings();
+      let mood_settings = &current_settings.mood;
+      let away = mood_settings
+        .decay
+        .is_away(idle::system_idle_ms(), idle::session_locked());
+      set_user_away(away);
+      let now = chrono::Local::now().naive_local();
+      let asleep = update_sleep(&current_settings.sleep, clock::minute_of_day(now));
+      let sleep_settings = asleep.map(|_| &current_settings.sleep);
+      if let Ok(mut engine) = mood_state().lock() {
+        engine.tick(mood_settings, now, elapsed_ms, away, sleep_settings);
+      }
+
+      let pumpkin_index = current_mood_engine().pumpkin_index();
+      let transition = sickness_state()
+        .lock()
+        .ok()
+        .and_then(|mut state| state.tick(&current_settings.sickness, pumpkin_index, elapsed_ms));
+      if let Some(transition) = transition {
+        record_sickness_transition(transition);
+      }
+
+      if let Ok(mut bond) = bond_state().lock() {
+        bond.tick(&current_settings.bond, now, elapsed_ms);
+      }
+
+      ticks += 1;
+      if ticks % PERSIST_EVERY == 0 {
+        persist_progress();
+      }
+    }
+  });
+}
+
+fn current_character_state() -> CharacterState {
+  CharacterState {
+    pumpkin_index: current_mood_engine().pumpkin_index(),
+    sulking: character_sulking_state()
+      .lock()
+      .map(|sulking| *sulking)
+      .unwrap_or(false),
+  }
+}
+
+fn current_chase_gait() -> Option<Gait> {
+  let state = current_character_state();
+  let current_settings = settings::current_settings();
+  let gait = gait::chase_gait(&current_settings.gait, state.pumpkin_index, state.sulking)?;
+  Some(if is_sick() {
+    gait.slowed(current_settings.sickness.speed_multiplier)
+  } else {
+    gait
+  })
+}
+
 fn set_pumpkin_dragging_state(is_dragging: bool) {
   if let Ok(mut dragging) = pumpkin_dragging_state().lock() {
     *dragging = is_dragging;
@@ -115,34 +706,99 @@ fn on_pumpkin_timeout() {
   }
   set_pumpkin_chase_timed_out(true);
   mark_pumpkin_timeout_pending();
+  queue_script_event(ScriptEvent::Timeout);
+}
+
+fn current_pumpkin_stage() -> PumpkinStage {
+  let elapsed_ms = pumpkin_spawned_at_state()
+    .lock()
+    .ok()
+    .and_then(|spawned_at| *spawned_at)
+    .map(|spawned_at| spawned_at.elapsed().as_millis() as u64)
+    .unwrap_or(0);
+  settings::current_settings().pumpkin_life.stage_at(elapsed_ms)
+}
+
+fn queue_pumpkin_stage_change(stage: PumpkinStage) {
+  if let Ok(mut pending) = pumpkin_stage_pending_state().lock() {
+    *pending = Some(stage);
+  }
+}
+
+fn take_pumpkin_stage_pending() -> Option<PumpkinStage> {
+  pumpkin_stage_pending_state()
+    .lock()
+    .ok()
+    .and_then(|mut pending| pending.take())
 }
 
-fn start_pumpkin_timeout_worker<R: tauri::Runtime>(app: tauri::AppHandle<R>, session_id: u64) {
+fn start_pumpkin_lifecycle_worker<R: tauri::Runtime>(
+  app: tauri::AppHandle<R>,
+  session_id: u64,
+  spawned_at: Instant,
+  arm_chase_timeout: bool,
+) {
   thread::spawn(move || {
-    thread::sleep(Duration::from_secs(5));
+    let life = settings::current_settings().pumpkin_life;
+    let mut stage = PumpkinStage::Fresh;
 
-    if current_pumpkin_session_id() != session_id {
-      return;
-    }
-    if app.get_webview_window("pumpkin").is_none() {
-      return;
-    }
+    while let Some(change_ms) = life.next_change_ms(spawned_at.elapsed().as_millis() as u64) {
+      while (spawned_at.elapsed().as_millis() as u64) < change_ms {
+        if current_pumpkin_session_id() != session_id {
+          return;
+        }
+        thread::sleep(Duration::from_millis(100));
+      }
+
+      if current_pumpkin_session_id() != session_id {
+        return;
+      }
+      if app.get_webview_window("pumpkin").is_none() {
+        return;
+      }
 
-    on_pumpkin_timeout();
+      if stage == PumpkinStage::Fresh && arm_chase_timeout && !is_pumpkin_reached() {
+        on_pumpkin_timeout();
+      }
+      stage = life.stage_at(change_ms);
+      queue_pumpkin_stage_change(stage);
+
+      if stage == PumpkinStage::Gone {
+        thread::sleep(Duration::from_millis(PUMPKIN_FADE_OUT_MS));
+        if current_pumpkin_session_id() != session_id {
+          return;
+        }
+        invalidate_pumpkin_session();
+        set_pumpkin_dragging_state(false);
+        if let Some(pumpkin_window) = app.get_webview_window("pumpkin") {
+          close_pumpkin_window(&pumpkin_window);
+        }
+        return;
+      }
+    }
   });
 }
 
-fn begin_pumpkin_session<R: tauri::Runtime>(app: tauri::AppHandle<R>) {
-  if let Ok(mut pending) = pumpkin_eaten_pending_state().lock() {
-    *pending = false;
+fn begin_pumpkin_session<R: tauri::Runtime>(app: tauri::AppHandle<R>, food: FoodItem, chasing: bool) {
+  if let Ok(mut progress) = meal_progress_state().lock() {
+    *progress = MealProgress::new(settings::current_settings().eating.bites);
+  }
+  if let Ok(mut current) = current_food_state().lock() {
+    *current = Some(food);
   }
   clear_pumpkin_timeout_pending();
+  let _ = take_pumpkin_stage_pending();
   set_pumpkin_chase_timed_out(false);
+  set_pumpkin_reached(false);
+  let spawned_at = Instant::now();
+  if let Ok(mut pumpkin_spawned_at) = pumpkin_spawned_at_state().lock() {
+    *pumpkin_spawned_at = Some(spawned_at);
+  }
   let session_id = next_pumpkin_session_id();
-  start_pumpkin_timeout_worker(app, session_id);
+  start_pumpkin_lifecycle_worker(app, session_id, spawned_at, chasing);
 }
 
-fn on_pumpkin_eaten() {
+fn finish_pumpkin_session() {
   invalidate_pumpkin_session();
   set_pumpkin_dragging_state(false);
   if let Ok(mut phase) = follow_phase_state().lock() {
@@ -150,45 +806,190 @@ fn on_pumpkin_eaten() {
   }
   set_pumpkin_chase_timed_out(false);
   clear_pumpkin_timeout_pending();
-  if let Ok(mut pending) = pumpkin_eaten_pending_state().lock() {
-    *pending = true;
-  }
 }
 
-fn take_pumpkin_eaten_pending() -> bool {
-  if let Ok(mut pending) = pumpkin_eaten_pending_state().lock() {
-    let was_pending = *pending;
-    *pending = false;
-    return was_pending;
-  }
-  false
+fn take_pumpkin_bites_pending() -> Vec<PumpkinBite> {
+  pumpkin_bites_pending_state()
+    .lock()
+    .map(|mut pending| std::mem::take(&mut *pending))
+    .unwrap_or_default()
 }
 
-fn try_start_follow_worker<R: tauri::Runtime>(app: tauri::AppHandle<R>) {
-  let should_start = if let Ok(mut running) = follow_worker_running_state().lock() {
-    if *running {
-      false
-    } else {
-      *running = true;
-      true
+fn shrink_pumpkin_window<R: tauri::Runtime>(pumpkin_window: &tauri::WebviewWindow<R>, target_size: u32) {
+  let (Ok(position), Ok(size)) = (pumpkin_window.outer_position(), pumpkin_window.outer_size()) else {
+    return;
+  };
+  let target_size = target_size.max(1);
+  let offset_x = (size.width as i32 - target_size as i32) / 2;
+  let offset_y = (size.height as i32 - target_size as i32) / 2;
+  let _ = pumpkin_window.set_size(PhysicalSize::new(target_size, target_size));
+  let _ = pumpkin_window.set_position(PhysicalPosition::new(position.x + offset_x, position.y + offset_y));
+}
+
+/// Takes one bite out of the current food. Returns `true` once nothing is
+/// left to eat and the window has been closed.
+fn take_pumpkin_bite<R: tauri::Runtime>(pumpkin_window: &tauri::WebviewWindow<R>) -> bool {
+  let Some(food) = current_food() else {
+    consume_pumpkin_window(pumpkin_window);
+    return true;
+  };
+  let current_settings = settings::current_settings();
+  let stage = current_pumpkin_stage();
+  let full_nutrition = food.nutrition * current_settings.pumpkin_life.nutrition_percent(stage) / 100;
+
+  let Some((outcome, scale)) = meal_progress_state().lock().ok().and_then(|mut progress| {
+    let outcome = progress.take_bite(full_nutrition)?;
+    Some((outcome, progress.window_scale(&current_settings.eating)))
+  }) else {
+    consume_pumpkin_window(pumpkin_window);
+    return true;
+  };
+
+  if outcome.finished && food.mood_effect != MoodEffect::Disliked {
+    record_care(Care::Meal);
+    let transition = sickness_state()
+      .lock()
+      .ok()
+      .and_then(|mut state| state.meal_finished(&current_settings.sickness));
+    if let Some(transition) = transition {
+      record_sickness_transition(transition);
     }
+  }
+
+  if outcome.finished {
+    queue_script_event(ScriptEvent::Eaten {
+      food: food.id.clone(),
+      nutrition: full_nutrition,
+    });
+  }
+
+  if outcome.finished && food.mood_effect == MoodEffect::FullRefill {
+    apply_mood(MoodTrigger::FullRefill);
   } else {
-    false
-  };
+    apply_mood(MoodTrigger::Fed {
+      nutrition: outcome.nutrition,
+    });
+  }
 
-  if !should_start {
-    return;
+  if let Ok(mut pending) = pumpkin_bites_pending_state().lock() {
+    pending.push(PumpkinBite {
+      food: food.id,
+      nutrition: outcome.nutrition,
+      bites_taken: outcome.bites_taken,
+      remaining_portion: outcome.remaining_portion,
+      finished: outcome.finished,
+      stage,
+      mood_effect: food.mood_effect,
+      voice: food.voice,
+    });
   }
 
-  thread::spawn(move || {
-    loop {
-      if !is_pumpkin_dragging() {
-        break;
-      }
+  if outcome.finished {
+    consume_pumpkin_window(pumpkin_window);
+    return true;
+  }
 
-      let Some(main_window) = app.get_webview_window("main") else {
-        break;
-      };
+  shrink_pumpkin_window(pumpkin_window, (food.window_size as f64 * scale).round() as u32);
+  false
+}
+
+fn pumpkin_touching_main<R: tauri::Runtime>(
+  main_window: &tauri::WebviewWindow<R>,
+  pumpkin_window: &tauri::WebviewWindow<R>,
+) -> bool {
+  let (Ok(main_pos), Ok(main_size)) = (main_window.outer_position(), main_window.outer_size()) else {
+    return false;
+  };
+  let (Ok(pumpkin_pos), Ok(pumpkin_size)) = (pumpkin_window.outer_position(), pumpkin_window.outer_size()) else {
+    return false;
+  };
+
+  touching_or_overlapping(
+    main_pos.x,
+    main_pos.y,
+    main_size.width as i32,
+    main_size.height as i32,
+    pumpkin_pos.x,
+    pumpkin_pos.y,
+    pumpkin_size.width as i32,
+    pumpkin_size.height as i32,
+  )
+}
+
+/// Bites the pumpkin at a fixed pace while it stays in contact with the
+/// character. Pulling it away ends the meal early and leaves the rest.
+fn try_start_meal_worker<R: tauri::Runtime>(app: tauri::AppHandle<R>) {
+  let should_start = if let Ok(mut running) = meal_worker_running_state().lock() {
+    if *running {
+      false
+    } else {
+      *running = true;
+      true
+    }
+  } else {
+    false
+  };
+
+  if !should_start {
+    return;
+  }
+
+  let session_id = current_pumpkin_session_id();
+  thread::spawn(move || {
+    let bite_interval = Duration::from_millis(settings::current_settings().eating.bite_interval_ms);
+    loop {
+      if current_pumpkin_session_id() != session_id {
+        break;
+      }
+      let (Some(main_window), Some(pumpkin_window)) =
+        (app.get_webview_window("main"), app.get_webview_window("pumpkin"))
+      else {
+        break;
+      };
+      if !pumpkin_touching_main(&main_window, &pumpkin_window) {
+        break;
+      }
+      if take_pumpkin_bite(&pumpkin_window) {
+        break;
+      }
+      thread::sleep(bite_interval);
+    }
+
+    if let Ok(mut running) = meal_worker_running_state().lock() {
+      *running = false;
+    }
+  });
+}
+
+fn try_start_follow_worker<R: tauri::Runtime>(app: tauri::AppHandle<R>) {
+  let should_start = if let Ok(mut running) = follow_worker_running_state().lock() {
+    if *running {
+      false
+    } else {
+      *running = true;
+      true
+    }
+  } else {
+    false
+  };
+
+  if !should_start {
+    return;
+  }
+
+  thread::spawn(move || {
+    let lease_id = acquire_motion(MotionOwner::Follow);
+    loop {
+      if !is_pumpkin_dragging() {
+        break;
+      }
+      let Some(lease_id) = lease_id.filter(|lease_id| holds_motion(*lease_id)) else {
+        break;
+      };
+
+      let Some(main_window) = app.get_webview_window("main") else {
+        break;
+      };
       let app_for_tick = app.clone();
       let _ = main_window.run_on_main_thread(move || {
         let Some(main_window) = app_for_tick.get_webview_window("main") else {
@@ -197,11 +998,14 @@ fn try_start_follow_worker<R: tauri::Runtime>(app: tauri::AppHandle<R>) {
         let Some(pumpkin_window) = app_for_tick.get_webview_window("pumpkin") else {
           return;
         };
-        let _ = follow_main_toward_pumpkin_windows(&main_window, &pumpkin_window);
+        let _ = follow_main_toward_pumpkin_windows(&main_window, &pumpkin_window, lease_id);
       });
       thread::sleep(Duration::from_millis(16));
     }
 
+    if let Some(lease_id) = lease_id {
+      release_motion(lease_id);
+    }
     if let Ok(mut running) = follow_worker_running_state().lock() {
       *running = false;
     }
@@ -272,7 +1076,429 @@ fn get_window_geometry(window: Window) -> Result<WindowGeometry, String> {
 fn set_window_position(window: Window, x: i32, y: i32) -> Result<(), String> {
   window
     .set_position(PhysicalPosition::new(x, y))
-    .map_err(|error| error.to_string())
+    .map_err(|error| error.to_string())?;
+  if let Ok(mut throw) = throw_state().lock() {
+    throw.tracker.push(x, y, uptime_ms());
+  }
+  Ok(())
+}
+
+fn throw_state() -> &'static Mutex<ThrowState> {
+  THROW.get_or_init(|| Mutex::new(ThrowState::default()))
+}
+
+fn uptime_ms() -> u64 {
+  APP_EPOCH.get_or_init(Instant::now).elapsed().as_millis() as u64
+}
+
+fn queue_throw_event(event: ThrowEvent) {
+  if let Ok(mut throw) = throw_state().lock() {
+    throw.events.push(event);
+  }
+}
+
+/// Flies the character until it comes to rest. Every way out, including a lost
+/// lease or a window error, ends with a `Settled` event so the frontend lifts
+/// the move lock it took at launch.
+fn run_throw(window: &Window, lease_id: u64, body: ThrowBody) -> Result<(), String> {
+  let result = fly_thrown_character(window, lease_id, body);
+  if !matches!(result, Ok(true)) {
+    queue_throw_event(ThrowEvent::Settled { landed: false });
+  }
+  result.map(|_| ())
+}
+
+/// Returns whether the flight ended with a `Settled` event of its own.
+fn fly_thrown_character(window: &Window, lease_id: u64, mut body: ThrowBody) -> Result<bool, String> {
+  const TICK: Duration = Duration::from_millis(16);
+  const MAX_FLIGHT: Duration = Duration::from_secs(8);
+
+  let app = window.app_handle();
+  let size = window.outer_size().map_err(|error| error.to_string())?;
+  let bounds = walk_bounds(window);
+  let started_at = Instant::now();
+  let mut last_tick = Instant::now();
+
+  loop {
+    thread::sleep(TICK);
+    let dt_ms = last_tick.elapsed().as_millis() as u64;
+    last_tick = Instant::now();
+
+    let throw_settings = settings::current_settings().throw;
+    let events = body.step(&throw_settings, dt_ms, bounds, size.width as i32, size.height as i32);
+    if !move_character(app, lease_id, body.x.round() as i32, body.y.round() as i32)? {
+      return Ok(false);
+    }
+
+    let mut settled = false;
+    for event in events {
+      match event {
+        ThrowEvent::Impact { hard: true, .. } => apply_mood(MoodTrigger::Drag),
+        ThrowEvent::Settled { .. } => settled = true,
+        _ => {}
+      }
+      queue_throw_event(event);
+    }
+    if settled {
+      return Ok(true);
+    }
+    if started_at.elapsed() >= MAX_FLIGHT {
+      queue_throw_event(ThrowEvent::Settled {
+        landed: throw_settings.ground_mode,
+      });
+      return Ok(true);
+    }
+  }
+}
+
+/// Called when the user lets go of the character. Flings it with the speed
+/// the drag had at release, or drops it to the ground in ground mode.
+/// Returns whether the character is now in flight.
+#[tauri::command]
+fn throw_character(window: Window) -> Result<bool, String> {
+  let throw_settings = settings::current_settings().throw;
+  let velocity = throw_state()
+    .lock()
+    .map(|mut throw| {
+      let velocity = throw.tracker.velocity(throw_settings.velocity_window_ms, uptime_ms());
+      throw.tracker.clear();
+      velocity
+    })
+    .unwrap_or((0.0, 0.0));
+  let fast_enough = velocity.0.hypot(velocity.1) >= throw_settings.min_speed;
+  if !throw_settings.enabled || !(fast_enough || throw_settings.ground_mode) {
+    return Ok(false);
+  }
+
+  let Some(lease_id) = acquire_motion(MotionOwner::Throw) else {
+    return Ok(false);
+  };
+  let position = match window.outer_position() {
+    Ok(position) => position,
+    Err(error) => {
+      release_motion(lease_id);
+      return Err(error.to_string());
+    }
+  };
+  let velocity = if fast_enough { velocity } else { (0.0, 0.0) };
+  let body = ThrowBody::launch(&throw_settings, position.x, position.y, velocity);
+
+  queue_throw_event(ThrowEvent::Launched);
+  thread::spawn(move || {
+    if let Err(error) = run_throw(&window, lease_id, body) {
+      log::warn!("throw stopped: {}", error);
+    }
+    release_motion(lease_id);
+  });
+  Ok(true)
+}
+
+#[tauri::command]
+fn take_throw_events() -> Vec<ThrowEvent> {
+  throw_state()
+    .lock()
+    .map(|mut throw| std::mem::take(&mut throw.events))
+    .unwrap_or_default()
+}
+
+/// Whether the frontend has moved the window itself just now, by a drag or
+/// its own wandering.
+fn frontend_moving() -> bool {
+  const RECENT_MS: u64 = 400;
+  throw_state()
+    .lock()
+    .ok()
+    .and_then(|throw| throw.tracker.last_sample_ms())
+    .is_some_and(|at| uptime_ms().saturating_sub(at) < RECENT_MS)
+}
+
+fn cursor_events_state() -> &'static Mutex<Vec<CursorEvent>> {
+  CURSOR_EVENTS.get_or_init(|| Mutex::new(Vec::new()))
+}
+
+fn queue_cursor_event(event: CursorEvent) {
+  if let Ok(mut events) = cursor_events_state().lock() {
+    events.push(event);
+  }
+}
+
+#[derive(Default)]
+struct CursorWorker {
+  walker: CursorWalker,
+  lease_id: Option<u64>,
+  walked_px: f64,
+  facing: Option<Facing>,
+}
+
+impl CursorWorker {
+  fn stop_walking(&mut self) {
+    self.walker.stop();
+    if let Some(lease_id) = self.lease_id.take() {
+      release_motion(lease_id);
+      queue_cursor_event(CursorEvent::WalkStopped);
+      apply_mood(MoodTrigger::Wander {
+        distance_px: std::mem::take(&mut self.walked_px),
+      });
+    }
+  }
+
+  fn tick<R: tauri::Runtime>(
+    &mut self,
+    app: &tauri::AppHandle<R>,
+    cursor_settings: &CursorSettings,
+    dt_ms: u64,
+  ) -> Result<(), String> {
+    if cursor_settings.mode == CursorMode::Off {
+      self.stop_walking();
+      self.facing = None;
+      return Ok(());
+    }
+    let Some(main_window) = app.get_webview_window("main") else {
+      return Ok(());
+    };
+    let position = main_window.outer_position().map_err(|error| error.to_string())?;
+    let size = main_window.outer_size().map_err(|error| error.to_string())?;
+    let cursor = main_window.cursor_position().map_err(|error| error.to_string())?;
+    let body = Rect::new(position.x, position.y, size.width as i32, size.height as i32);
+    let bounds = walk_bounds(&main_window.as_ref().window());
+
+    let resting = current_sleep().is_some() || frontend_moving();
+    let step = if resting || (is_sick() && cursor_settings.mode == CursorMode::Follow) {
+      None
+    } else {
+      self.walker.step(
+        cursor_settings,
+        body,
+        (cursor.x, cursor.y),
+        (bounds.x, bounds.right() - body.width),
+        dt_ms,
+      )
+    };
+
+    let Some(step) = step else {
+      self.stop_walking();
+      let idle = motion_state().lock().map(|motion| motion.is_free()).unwrap_or(false);
+      if cursor_settings.mode == CursorMode::LookAt && idle && !resting {
+        let facing = cursor::facing(cursor_settings, body, cursor.x);
+        if let Some(facing) = facing.filter(|facing| self.facing != Some(*facing)) {
+          self.facing = Some(facing);
+          queue_cursor_event(CursorEvent::Face { facing });
+        }
+      } else {
+        self.facing = None;
+      }
+      return Ok(());
+    };
+
+    let lease_id = match self.lease_id {
+      Some(lease_id) => lease_id,
+      None => {
+        let Some(lease_id) = acquire_motion(MotionOwner::Cursor) else {
+          self.walker.stop();
+          return Ok(());
+        };
+        queue_cursor_event(CursorEvent::WalkStarted);
+        self.lease_id = Some(lease_id);
+        lease_id
+      }
+    };
+    if move_character(app, lease_id, position.x + step.round() as i32, position.y)? {
+      self.walked_px += step.abs();
+    } else {
+      // Something more important took the character over.
+      self.stop_walking();
+    }
+    Ok(())
+  }
+}
+
+/// Samples the global cursor and walks after it, away from it or turns toward
+/// it, depending on the cursor mode.
+fn start_cursor_worker(app: tauri::AppHandle) {
+  /// A failure that keeps happening is logged once per this long.
+  const WARN_EVERY: Duration = Duration::from_secs(60);
+
+  thread::spawn(move || {
+    let mut worker = CursorWorker::default();
+    let mut last_tick = Instant::now();
+    let mut last_warning: Option<Instant> = None;
+    let mut suppressed = 0u32;
+    loop {
+      let cursor_settings = settings::current_settings().cursor;
+      thread::sleep(Duration::from_millis(cursor_settings.sample_interval_ms.max(10)));
+      let dt_ms = last_tick.elapsed().as_millis() as u64;
+      last_tick = Instant::now();
+
+      if let Err(error) = worker.tick(&app, &cursor_settings, dt_ms) {
+        if !last_warning.is_some_and(|warned_at| warned_at.elapsed() < WARN_EVERY) {
+          if suppressed > 0 {
+            log::warn!("cursor tracking failed: {} ({} more since the last warning)", error, suppressed);
+          } else {
+            log::warn!("cursor tracking failed: {}", error);
+          }
+          last_warning = Some(Instant::now());
+          suppressed = 0;
+        } else {
+          suppressed += 1;
+        }
+        worker.stop_walking();
+      }
+    }
+  });
+}
+
+#[tauri::command]
+fn take_cursor_events() -> Vec<CursorEvent> {
+  cursor_events_state()
+    .lock()
+    .map(|mut events| std::mem::take(&mut *events))
+    .unwrap_or_default()
+}
+
+fn script_events_state() -> &'static Mutex<Vec<ScriptEvent>> {
+  SCRIPT_EVENTS.get_or_init(|| Mutex::new(Vec::new()))
+}
+
+fn script_commands_state() -> &'static Mutex<Vec<ScriptCommand>> {
+  SCRIPT_COMMANDS.get_or_init(|| Mutex::new(Vec::new()))
+}
+
+fn queue_script_event(event: ScriptEvent) {
+  if !settings::current_settings().scripting.enabled {
+    return;
+  }
+  if let Ok(mut events) = script_events_state().lock() {
+    events.push(event);
+  }
+}
+
+fn run_script_command(app: &tauri::AppHandle, command: ScriptCommand) {
+  let ScriptCommand::SpawnPumpkin { food } = command else {
+    if let Ok(mut commands) = script_commands_state().lock() {
+      commands.push(command);
+    }
+    return;
+  };
+
+  let Some(main_window) = app.get_webview_window("main") else {
+    return;
+  };
+  let catalog = food::current_catalog();
+  let food = food
+    .as_deref()
+    .and_then(|id| catalog.find(id))
+    .or_else(|| catalog.pick_weighted(time_seed()))
+    .cloned();
+  let Some(food) = food else {
+    return;
+  };
+  if let Err(error) = spawn_food_window(&main_window.as_ref().window(), food) {
+    log::warn!("script spawn_pumpkin failed: {}", error);
+  }
+}
+
+/// Runs user scripts for queued events, off the thread that raised them, and
+/// picks up edits to the scripts folder while the app runs.
+fn start_script_worker(app: tauri::AppHandle, scripts_dir: PathBuf) {
+  const TICK: Duration = Duration::from_millis(100);
+
+  thread::spawn(move || {
+    let current_hour = || clock::minute_of_day(chrono::Local::now().naive_local()) / 60;
+    let mut host = ScriptHost::new();
+    let mut last_reload: Option<Instant> = None;
+    let mut last_hour = current_hour();
+    loop {
+      thread::sleep(TICK);
+      let scripting_settings = settings::current_settings().scripting;
+      if !scripting_settings.enabled {
+        continue;
+      }
+
+      let reload_interval = Duration::from_millis(scripting_settings.reload_interval_ms);
+      if !last_reload.is_some_and(|at| at.elapsed() < reload_interval) {
+        host.reload(&scripts_dir);
+        last_reload = Some(Instant::now());
+      }
+
+      let hour = current_hour();
+      if hour != last_hour {
+        last_hour = hour;
+        queue_script_event(ScriptEvent::HourChanged { hour });
+      }
+
+      let events = script_events_state()
+        .lock()
+        .map(|mut events| std::mem::take(&mut *events))
+        .unwrap_or_default();
+      for event in events {
+        for command in host.dispatch(&scripting_settings, &event) {
+          run_script_command(&app, command);
+        }
+      }
+    }
+  });
+}
+
+#[tauri::command]
+fn take_script_commands() -> Vec<ScriptCommand> {
+  script_commands_state()
+    .lock()
+    .map(|mut commands| std::mem::take(&mut *commands))
+    .unwrap_or_default()
+}
+
+/// Decides what the character does next from the behavior tree.
+#[tauri::command]
+fn plan_activities(window: Window) -> Plan {
+  let snapshot = mood_snapshot();
+  let idle_secs = sleep_state()
+    .lock()
+    .map(|state| state.last_interaction.elapsed().as_secs())
+    .unwrap_or(0);
+  let now = chrono::Local::now().naive_local();
+  let board = Blackboard {
+    pumpkin_index: snapshot.pumpkin_index,
+    energy: snapshot.energy,
+    fun: snapshot.fun,
+    mood: snapshot.mood,
+    satisfied: snapshot.satisfied,
+    asleep: snapshot.asleep.is_some(),
+    sick: snapshot.sick,
+    food_present: window.app_handle().get_webview_window("pumpkin").is_some(),
+    minute_of_day: clock::minute_of_day(now),
+    idle_secs,
+  };
+  behavior::current_tree().plan(&board, time_seed())
+}
+
+#[tauri::command]
+fn take_nap(secs: u64) {
+  if let Ok(mut state) = sleep_state().lock() {
+    state.nap_until = Some(Instant::now() + Duration::from_secs(secs));
+    state.asleep.get_or_insert(SleepReason::Nap);
+  }
+}
+
+/// Walks over to the food already on screen. Returns whether a walk started.
+#[tauri::command]
+fn seek_pumpkin(window: Window) -> Result<bool, String> {
+  const PUMPKIN_LABEL: &str = "pumpkin";
+
+  let Some(pumpkin_window) = window.app_handle().get_webview_window(PUMPKIN_LABEL) else {
+    return Ok(false);
+  };
+  let disliked = current_food().is_some_and(|food| food.mood_effect == MoodEffect::Disliked);
+  let Some(chase_gait) = current_chase_gait().filter(|_| !disliked) else {
+    return Ok(false);
+  };
+  if !motion_state().lock().map(|motion| motion.is_free()).unwrap_or(false) {
+    return Ok(false);
+  }
+
+  let position = pumpkin_window.outer_position().map_err(|error| error.to_string())?;
+  let size = pumpkin_window.outer_size().map_err(|error| error.to_string())?;
+  chase_food_window(&window, position.x, position.y, size.width as i32, PUMPKIN_LABEL, chase_gait)?;
+  Ok(true)
 }
 
 fn random_between(seed: u64, min: i32, max: i32) -> i32 {
@@ -309,29 +1535,7 @@ fn monitor_bounds(window: &Window) -> Result<(i32, i32, u32, u32), String> {
   }
 }
 
-fn touching_or_overlapping(
-  ax: i32,
-  ay: i32,
-  aw: i32,
-  ah: i32,
-  bx: i32,
-  by: i32,
-  bw: i32,
-  bh: i32,
-) -> bool {
-  let a_left = ax;
-  let a_right = ax + aw;
-  let a_top = ay;
-  let a_bottom = ay + ah;
-  let b_left = bx;
-  let b_right = bx + bw;
-  let b_top = by;
-  let b_bottom = by + bh;
-
-  !(a_right < b_left || a_left > b_right || a_bottom < b_top || a_top > b_bottom)
-}
-
-fn close_pumpkin_if_touching(
+fn reach_pumpkin_if_touching(
   window: &Window,
   pumpkin_label: &str,
   main_x: i32,
@@ -362,14 +1566,17 @@ fn close_pumpkin_if_touching(
   );
 
   if touching {
-    consume_pumpkin_window(&pumpkin_window);
+    set_pumpkin_reached(true);
+    if !should_carry_current_food() || !try_start_carry_worker(window.app_handle().clone()) {
+      try_start_meal_worker(pumpkin_window.app_handle().clone());
+    }
     return true;
   }
 
   false
 }
 
-fn consume_pumpkin_window<R: tauri::Runtime>(pumpkin_window: &tauri::WebviewWindow<R>) {
+fn close_pumpkin_window<R: tauri::Runtime>(pumpkin_window: &tauri::WebviewWindow<R>) {
   let _ = pumpkin_window.hide();
   let _ = pumpkin_window.close();
 
@@ -386,11 +1593,14 @@ fn consume_pumpkin_window<R: tauri::Runtime>(pumpkin_window: &tauri::WebviewWind
       thread::sleep(Duration::from_millis(16));
     }
   });
+}
 
-  on_pumpkin_eaten();
+fn consume_pumpkin_window<R: tauri::Runtime>(pumpkin_window: &tauri::WebviewWindow<R>) {
+  close_pumpkin_window(pumpkin_window);
+  finish_pumpkin_session();
 }
 
-fn close_dragging_pumpkin_if_touching<R: tauri::Runtime>(
+fn eat_dragging_pumpkin_if_touching<R: tauri::Runtime>(
   pumpkin_window: &tauri::WebviewWindow<R>,
   main_x: i32,
   main_y: i32,
@@ -416,22 +1626,78 @@ fn close_dragging_pumpkin_if_touching<R: tauri::Runtime>(
   );
 
   if touching {
-    consume_pumpkin_window(pumpkin_window);
+    set_pumpkin_reached(true);
+    try_start_meal_worker(pumpkin_window.app_handle().clone());
     return true;
   }
 
   false
 }
 
-fn walk_window_to(
+fn collect_walk_keep_out_zones(window: &Window, pumpkin_label: &str) -> Vec<Rect> {
+  let walk_settings = settings::current_settings().walk;
+  let mut zones = walk_settings.keep_out_zones.clone();
+
+  if walk_settings.avoid_cursor {
+    if let Ok(cursor) = window.cursor_position() {
+      let radius = walk_settings.cursor_keep_out_radius.max(0);
+      zones.push(Rect::new(
+        cursor.x.round() as i32 - radius,
+        cursor.y.round() as i32 - radius,
+        radius * 2,
+        radius * 2,
+      ));
+    }
+  }
+
+  if walk_settings.avoid_other_windows {
+    for (label, other_window) in window.app_handle().webview_windows() {
+      if label == window.label() || label == pumpkin_label {
+        continue;
+      }
+      if !other_window.is_visible().unwrap_or(false) {
+        continue;
+      }
+      let (Ok(position), Ok(size)) = (other_window.outer_position(), other_window.outer_size()) else {
+        continue;
+      };
+      zones.push(Rect::new(position.x, position.y, size.width as i32, size.height as i32));
+    }
+  }
+
+  zones
+}
+
+fn walk_bounds(window: &Window) -> Rect {
+  monitor_bounds(window)
+    .map(|(x, y, width, height)| Rect::new(x, y, width as i32, height as i32))
+    .unwrap_or(Rect::new(0, 0, 1920, 1080))
+}
+
+fn plan_walk_path(
   window: &Window,
-  start_x: i32,
-  start_y: i32,
-  target_x: i32,
-  target_y: i32,
+  start: (i32, i32),
+  target: (i32, i32),
   main_width: i32,
   main_height: i32,
-  initial_pumpkin_x: i32,
+  pumpkin_label: &str,
+) -> Vec<(i32, i32)> {
+  let bounds = walk_bounds(window);
+  let zones = collect_walk_keep_out_zones(window, pumpkin_label);
+  let cell_size = settings::current_settings().walk.grid_cell_size;
+
+  path_planner::plan_path(start, target, main_width, main_height, bounds, &zones, cell_size)
+    .unwrap_or_else(|| vec![start, target])
+}
+
+fn walk_window_along(
+  window: &Window,
+  lease_id: u64,
+  waypoints: &[(i32, i32)],
+  gait: &Gait,
+  main_width: i32,
+  main_height: i32,
+  initial_pumpkin_x: i32,
   initial_pumpkin_y: i32,
   pumpkin_label: &str,
 ) -> Result<bool, String> {
@@ -439,22 +1705,20 @@ fn walk_window_to(
     return Ok(false);
   }
 
-  let delta_x = target_x - start_x;
-  let delta_y = target_y - start_y;
-  let distance = ((delta_x as f64).powi(2) + (delta_y as f64).powi(2)).sqrt();
+  let Some(&(target_x, target_y)) = waypoints.last() else {
+    return Ok(false);
+  };
+  let distance = path_planner::path_length(waypoints);
 
   if distance < 1.0 {
     return Ok(false);
   }
+  apply_mood(MoodTrigger::Wander { distance_px: distance });
 
-  let mut steps = (distance / 4.0).ceil() as i32;
+  let mut steps = (distance / gait.step_px).ceil() as i32;
   steps = clamp_i32(steps, 24, 320);
-  let direction_x = delta_x as f64 / distance;
-  let direction_y = delta_y as f64 / distance;
-  let perp_x = -direction_y;
-  let perp_y = direction_x;
-  let gait_cycles = clamp_i32((distance / 34.0).round() as i32, 5, 20) as f64;
-  let wobble_amp = if distance < 180.0 { 1.3 } else { 2.0 };
+  let gait_cycles = clamp_i32((distance / gait.gait_cycle_px).round() as i32, 5, 20) as f64;
+  let wobble_amp = if distance < 180.0 { 1.3 } else { 2.0 } * gait.wobble_scale;
 
   for step in 1..=steps {
     if is_pumpkin_chase_timed_out() {
@@ -474,23 +1738,24 @@ fn walk_window_to(
 
     let t = step as f64 / steps as f64;
     let eased_t = 0.5 - 0.5 * (std::f64::consts::PI * t).cos();
-    let base_x = start_x as f64 + (delta_x as f64) * eased_t;
-    let base_y = start_y as f64 + (delta_y as f64) * eased_t;
+    let ((base_x, base_y), (direction_x, direction_y)) = path_planner::point_along(waypoints, distance * eased_t);
+    let perp_x = -direction_y;
+    let perp_y = direction_x;
     let gait_wave = (2.0 * std::f64::consts::PI * gait_cycles * t).sin();
     let crawl_drop = gait_wave.abs() * 0.8;
     let x = (base_x + perp_x * gait_wave * wobble_amp).round() as i32;
     let y = (base_y + perp_y * gait_wave * wobble_amp + crawl_drop).round() as i32;
 
-    window
-      .set_position(PhysicalPosition::new(x, y))
-      .map_err(|error| error.to_string())?;
+    if !move_character(window.app_handle(), lease_id, x, y)? {
+      return Ok(false);
+    }
 
-    if close_pumpkin_if_touching(window, pumpkin_label, x, y, main_width, main_height) {
+    if reach_pumpkin_if_touching(window, pumpkin_label, x, y, main_width, main_height) {
       return Ok(true);
     }
 
     let edge_slow = (1.0 - (std::f64::consts::PI * t).sin()).max(0.0);
-    let delay_ms = 8.0 + edge_slow * 4.0;
+    let delay_ms = gait.base_delay_ms + edge_slow * gait.edge_slow_delay_ms;
     thread::sleep(Duration::from_millis(delay_ms.round() as u64));
   }
 
@@ -498,11 +1763,11 @@ fn walk_window_to(
     return Ok(false);
   }
 
-  window
-    .set_position(PhysicalPosition::new(target_x, target_y))
-    .map_err(|error| error.to_string())?;
+  if !move_character(window.app_handle(), lease_id, target_x, target_y)? {
+    return Ok(false);
+  }
 
-  if close_pumpkin_if_touching(window, pumpkin_label, target_x, target_y, main_width, main_height) {
+  if reach_pumpkin_if_touching(window, pumpkin_label, target_x, target_y, main_width, main_height) {
     return Ok(true);
   }
 
@@ -518,121 +1783,830 @@ fn start_walk_to_pumpkin_worker(
   initial_pumpkin_x: i32,
   initial_pumpkin_y: i32,
   pumpkin_label: &'static str,
+  gait: Gait,
 ) {
   thread::spawn(move || {
+    let Some(lease_id) = acquire_motion(MotionOwner::Chase) else {
+      return;
+    };
     let Ok(start_position) = window.outer_position() else {
+      release_motion(lease_id);
       return;
     };
 
-    let _ = walk_window_to(
-      &window,
-      start_position.x,
-      start_position.y,
-      target_x,
-      target_y,
-      main_width,
-      main_height,
-      initial_pumpkin_x,
-      initial_pumpkin_y,
-      pumpkin_label,
-    );
-  });
+    let waypoints = plan_walk_path(
+      &window,
+      (start_position.x, start_position.y),
+      (target_x, target_y),
+      main_width,
+      main_height,
+      pumpkin_label,
+    );
+
+    let _ = walk_window_along(
+      &window,
+      lease_id,
+      &waypoints,
+      &gait,
+      main_width,
+      main_height,
+      initial_pumpkin_x,
+      initial_pumpkin_y,
+      pumpkin_label,
+    );
+    release_motion(lease_id);
+  });
+}
+
+fn should_carry_current_food() -> bool {
+  let Some(food) = current_food() else {
+    return false;
+  };
+  let character = current_character_state();
+  settings::current_settings()
+    .carry
+    .should_carry(character.pumpkin_index, food.mood_effect, save::current_save().stash.len())
+}
+
+fn queue_pumpkin_stashed(stash_count: u32) {
+  if let Ok(mut pending) = pumpkin_stashed_pending_state().lock() {
+    *pending = Some(stash_count);
+  }
+}
+
+fn stash_pumpkin_window(pumpkin_window: &tauri::WebviewWindow) {
+  let food_id = current_food()
+    .map(|food| food.id)
+    .unwrap_or_else(|| "pumpkin".into());
+  if let Err(error) = save::update_save(|save| save.stash.push(food_id)) {
+    log::warn!("failed to write stash: {}", error);
+  }
+
+  close_pumpkin_window(pumpkin_window);
+  finish_pumpkin_session();
+  queue_pumpkin_stashed(save::current_save().stash.len() as u32);
+}
+
+/// Picks the pumpkin up and walks it over to the stash. Dragging the pumpkin
+/// takes the motion lease away and drops it where it is.
+fn carry_pumpkin_to_stash(app: &tauri::AppHandle, lease_id: u64) -> Result<(), String> {
+  let carry = settings::current_settings().carry;
+  let (Some(main_window), Some(pumpkin_window)) = (app.get_webview_window("main"), app.get_webview_window("pumpkin"))
+  else {
+    return Ok(());
+  };
+
+  let main_pos = main_window.outer_position().map_err(|error| error.to_string())?;
+  let main_size = main_window.outer_size().map_err(|error| error.to_string())?;
+  let pumpkin_size = pumpkin_window.outer_size().map_err(|error| error.to_string())?;
+  let main_w = main_size.width as i32;
+  let main_h = main_size.height as i32;
+  let (offset_x, offset_y) = carry.hold_offset(
+    main_w,
+    main_h,
+    pumpkin_size.width as i32,
+    pumpkin_size.height as i32,
+  );
+
+  let attached = motion_state().lock().is_ok_and(|mut motion| {
+    motion.attach(
+      lease_id,
+      Attachment {
+        label: "pumpkin".into(),
+        offset_x,
+        offset_y,
+      },
+    )
+  });
+  if !attached || !move_character(app, lease_id, main_pos.x, main_pos.y)? {
+    return Ok(());
+  }
+
+  let window = main_window.as_ref().window();
+  let target = carry.stash_target(walk_bounds(&window), main_w, main_h);
+  let waypoints = plan_walk_path(&window, (main_pos.x, main_pos.y), target, main_w, main_h, "pumpkin");
+  let distance = path_planner::path_length(&waypoints);
+  apply_mood(MoodTrigger::Wander { distance_px: distance });
+  let gait = current_chase_gait().unwrap_or_default();
+  let steps = clamp_i32((distance / gait.step_px).ceil() as i32, 1, 400);
+
+  for step in 1..=steps {
+    if app.get_webview_window("pumpkin").is_none() {
+      return Ok(());
+    }
+
+    let t = step as f64 / steps as f64;
+    let ((x, y), _) = path_planner::point_along(&waypoints, distance * t);
+    let bob = if step % 4 == 1 { 1.0 } else { 0.0 };
+    if !move_character(app, lease_id, x.round() as i32, (y + bob).round() as i32)? {
+      return Ok(());
+    }
+    thread::sleep(Duration::from_millis(gait.base_delay_ms.round() as u64));
+  }
+
+  if holds_motion(lease_id) {
+    stash_pumpkin_window(&pumpkin_window);
+  }
+  Ok(())
+}
+
+fn try_start_carry_worker(app: tauri::AppHandle) -> bool {
+  let Some(lease_id) = acquire_motion(MotionOwner::Carry) else {
+    return false;
+  };
+
+  thread::spawn(move || {
+    if let Err(error) = carry_pumpkin_to_stash(&app, lease_id) {
+      log::warn!("pumpkin carry stopped: {}", error);
+    }
+    release_motion(lease_id);
+  });
+  true
+}
+
+fn time_seed() -> u64 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|duration| duration.as_nanos() as u64)
+    .unwrap_or(0)
+}
+
+#[tauri::command]
+async fn spawn_food(window: Window, kind: Option<String>) -> Result<bool, String> {
+  let catalog = food::current_catalog();
+  let food = match kind {
+    Some(kind) => catalog
+      .find(&kind)
+      .cloned()
+      .ok_or_else(|| format!("unknown food `{kind}`"))?,
+    None => catalog
+      .pick_weighted(time_seed().rotate_left(29))
+      .cloned()
+      .ok_or_else(|| "food catalog is empty".to_string())?,
+  };
+
+  spawn_food_window(&window, food)
+}
+
+#[tauri::command]
+fn get_current_food() -> Option<FoodItem> {
+  current_food()
+}
+
+fn random_food_position(window: &Window, size: i32) -> Result<(i32, i32), String> {
+  let (monitor_x, monitor_y, monitor_width, monitor_height) = monitor_bounds(window)?;
+
+  let max_x = monitor_x + (monitor_width as i32 - size).max(0);
+  let max_y = monitor_y + (monitor_height as i32 - size).max(0);
+  let time_seed = time_seed();
+  let position_seed = window
+    .outer_position()
+    .map(|position| ((position.x as i64 as u64) << 32) ^ (position.y as i64 as u64))
+    .unwrap_or(0);
+
+  let x = random_between(time_seed ^ position_seed ^ 0x9E37_79B9_7F4A_7C15, monitor_x, max_x);
+  let y = random_between(
+    time_seed.rotate_left(17) ^ position_seed ^ 0xC2B2_AE3D_27D4_EB4F,
+    monitor_y,
+    max_y,
+  );
+  Ok((x, y))
+}
+
+/// Opens a transparent food window showing `page`, replacing any window that
+/// already uses `label`.
+fn open_food_window(
+  window: &Window,
+  label: &str,
+  page: &str,
+  title: &str,
+  x: i32,
+  y: i32,
+  size: u32,
+) -> Result<(), String> {
+  let app = window.app_handle();
+  if let Some(existing) = app.get_webview_window(label) {
+    let _ = existing.close();
+  }
+
+  WebviewWindowBuilder::new(app, label, WebviewUrl::App(page.into()))
+    .title(title)
+    .position(x as f64, y as f64)
+    .inner_size(size as f64, size as f64)
+    .resizable(false)
+    .always_on_top(true)
+    .skip_taskbar(true)
+    .decorations(false)
+    .transparent(true)
+    .shadow(false)
+    .focused(false)
+    .build()
+    .map_err(|error| error.to_string())?;
+  Ok(())
+}
+
+fn spawn_food_window(window: &Window, food: FoodItem) -> Result<bool, String> {
+  const PUMPKIN_LABEL: &str = "pumpkin";
+
+  set_pumpkin_dragging_state(false);
+  note_interaction();
+
+  let pumpkin_size = food.window_size as i32;
+  let (pumpkin_x, pumpkin_y) = random_food_position(window, pumpkin_size)?;
+  open_food_window(window, PUMPKIN_LABEL, "pumpkin.html", &food.label, pumpkin_x, pumpkin_y, food.window_size)?;
+
+  let chase_gait = current_chase_gait().filter(|_| food.mood_effect != MoodEffect::Disliked);
+  begin_pumpkin_session(window.app_handle().clone(), food, chase_gait.is_some());
+  if let Some(chase_gait) = chase_gait {
+    chase_food_window(window, pumpkin_x, pumpkin_y, pumpkin_size, PUMPKIN_LABEL, chase_gait)?;
+  }
+  Ok(false)
+}
+
+/// Walks the character up to the side of the food window at `pumpkin_x`,
+/// `pumpkin_y`.
+fn chase_food_window(
+  window: &Window,
+  pumpkin_x: i32,
+  pumpkin_y: i32,
+  pumpkin_size: i32,
+  pumpkin_label: &'static str,
+  chase_gait: Gait,
+) -> Result<(), String> {
+  let (monitor_x, monitor_y, monitor_width, monitor_height) = monitor_bounds(window)?;
+  let main_size = window.outer_size().map_err(|error| error.to_string())?;
+  let main_width = main_size.width as i32;
+  let main_height = main_size.height as i32;
+  let gap = 0;
+
+  let left_candidate = pumpkin_x - main_width - gap;
+  let right_candidate = pumpkin_x + pumpkin_size + gap;
+  let centered_x = pumpkin_x + pumpkin_size / 2 - main_width / 2;
+  let min_main_x = monitor_x;
+  let max_main_x = monitor_x + (monitor_width as i32 - main_width).max(0);
+  let target_x = if left_candidate >= min_main_x {
+    left_candidate
+  } else if right_candidate <= max_main_x {
+    right_candidate
+  } else {
+    clamp_i32(centered_x, min_main_x, max_main_x)
+  };
+
+  let centered_y = pumpkin_y + pumpkin_size / 2 - main_height / 2;
+  let min_main_y = monitor_y;
+  let max_main_y = monitor_y + (monitor_height as i32 - main_height).max(0);
+  let target_y = clamp_i32(centered_y, min_main_y, max_main_y);
+
+  start_walk_to_pumpkin_worker(
+    window.clone(),
+    target_x,
+    target_y,
+    main_width,
+    main_height,
+    pumpkin_x,
+    pumpkin_y,
+    pumpkin_label,
+    chase_gait,
+  );
+
+  Ok(())
+}
+
+fn queue_flee_result(outcome: FleeOutcome) {
+  apply_mood(MoodTrigger::Play);
+  let record = save::update_save(|save| {
+    save.flee.record(outcome);
+    save.flee.clone()
+  })
+  .unwrap_or_else(|error| {
+    log::warn!("failed to write flee record: {}", error);
+    save::current_save().flee
+  });
+
+  if let Ok(mut pending) = flee_result_pending_state().lock() {
+    *pending = Some(FleeResult { outcome, record });
+  }
+}
+
+/// Runs one round of the flee game. Returns `None` when the round was called
+/// off, e.g. because the pumpkin was dragged or replaced.
+fn run_flee_round(app: &tauri::AppHandle, session_id: u64, lease_id: u64) -> Result<Option<FleeOutcome>, String> {
+  let flee = settings::current_settings().flee;
+  let started_at = Instant::now();
+
+  loop {
+    if current_pumpkin_session_id() != session_id || is_pumpkin_dragging() {
+      return Ok(None);
+    }
+    let (Some(main_window), Some(pumpkin_window)) = (app.get_webview_window("main"), app.get_webview_window("pumpkin"))
+    else {
+      return Ok(None);
+    };
+
+    let main_pos = main_window.outer_position().map_err(|error| error.to_string())?;
+    let main_size = main_window.outer_size().map_err(|error| error.to_string())?;
+    let pumpkin_pos = pumpkin_window.outer_position().map_err(|error| error.to_string())?;
+    let pumpkin_size = pumpkin_window.outer_size().map_err(|error| error.to_string())?;
+    let main = Rect::new(main_pos.x, main_pos.y, main_size.width as i32, main_size.height as i32);
+    let pumpkin = Rect::new(
+      pumpkin_pos.x,
+      pumpkin_pos.y,
+      pumpkin_size.width as i32,
+      pumpkin_size.height as i32,
+    );
+
+    let caught = touching_or_overlapping(
+      main.x,
+      main.y,
+      main.width,
+      main.height,
+      pumpkin.x,
+      pumpkin.y,
+      pumpkin.width,
+      pumpkin.height,
+    );
+    if let Some(outcome) = flee.outcome(started_at.elapsed().as_millis() as u64, caught) {
+      return Ok(Some(outcome));
+    }
+
+    let mut threats = vec![Threat {
+      x: main.x as f64 + main.width as f64 / 2.0,
+      y: main.y as f64 + main.height as f64 / 2.0,
+      weight: 1.0,
+    }];
+    if flee.avoid_cursor {
+      if let Ok(cursor) = main_window.cursor_position() {
+        threats.push(Threat {
+          x: cursor.x,
+          y: cursor.y,
+          weight: flee.cursor_weight,
+        });
+      }
+    }
+    let bounds = walk_bounds(&main_window.as_ref().window());
+    let (next_x, next_y) = flee.flee_step(pumpkin, &threats, bounds);
+    let _ = pumpkin_window.set_position(PhysicalPosition::new(next_x, next_y));
+
+    let target_x = (next_x + pumpkin.width / 2 - main.width / 2) as f64;
+    let target_y = (next_y + pumpkin.height / 2 - main.height / 2) as f64;
+    let delta_x = target_x - main.x as f64;
+    let delta_y = target_y - main.y as f64;
+    let distance = delta_x.hypot(delta_y);
+    let speed = flee.chaser_speed_px * current_chase_gait().unwrap_or_default().speed_factor();
+    if distance > 0.5 {
+      let step = speed.min(distance);
+      let chaser_x = (main.x as f64 + delta_x / distance * step).round() as i32;
+      let chaser_y = (main.y as f64 + delta_y / distance * step).round() as i32;
+      if !move_character(app, lease_id, chaser_x, chaser_y)? {
+        return Ok(None);
+      }
+    }
+
+    thread::sleep(Duration::from_millis(16));
+  }
+}
+
+#[tauri::command]
+async fn start_flee_game(window: Window) -> Result<(), String> {
+  let catalog = food::current_catalog();
+  let Some(food) = catalog
+    .find("pumpkin")
+    .or_else(|| catalog.foods.iter().find(|food| food.mood_effect != MoodEffect::Disliked))
+    .cloned()
+  else {
+    return Err("no food to play with".into());
+  };
+  let Some(lease_id) = acquire_motion(MotionOwner::Chase) else {
+    return Err("Speaki is busy".into());
+  };
+
+  set_pumpkin_dragging_state(false);
+  let (pumpkin_x, pumpkin_y) = match random_food_position(&window, food.window_size as i32) {
+    Ok(position) => position,
+    Err(error) => {
+      release_motion(lease_id);
+      return Err(error);
+    }
+  };
+  if let Err(error) = open_food_window(&window, "pumpkin", "pumpkin.html", &food.label, pumpkin_x, pumpkin_y, food.window_size) {
+    release_motion(lease_id);
+    return Err(error);
+  }
+  begin_pumpkin_session(window.app_handle().clone(), food, false);
+  let session_id = current_pumpkin_session_id();
+
+  let app = window.app_handle().clone();
+  thread::spawn(move || {
+    let outcome = run_flee_round(&app, session_id, lease_id).unwrap_or_else(|error| {
+      log::warn!("flee game stopped: {}", error);
+      None
+    });
+    release_motion(lease_id);
+    let Some(outcome) = outcome else {
+      return;
+    };
+
+    match outcome {
+      FleeOutcome::Caught { .. } => {
+        set_pumpkin_reached(true);
+        try_start_meal_worker(app.clone());
+      }
+      FleeOutcome::Escaped => {
+        if let Some(pumpkin_window) = app.get_webview_window("pumpkin") {
+          close_pumpkin_window(&pumpkin_window);
+        }
+        finish_pumpkin_session();
+      }
+    }
+    queue_flee_result(outcome);
+  });
+
+  Ok(())
+}
+
+#[tauri::command]
+fn take_flee_result() -> Option<FleeResult> {
+  flee_result_pending_state()
+    .lock()
+    .ok()
+    .and_then(|mut pending| pending.take())
+}
+
+#[tauri::command]
+fn get_flee_record() -> FleeRecord {
+  save::current_save().flee
+}
+
+fn catch_item_label(id: u64) -> String {
+  format!("catch-{id}")
+}
+
+fn queue_catch_update(caught: u32, missed: u32, nutrition: u32, score: u32, finished: bool) {
+  if let Ok(mut pending) = catch_update_pending_state().lock() {
+    let update = pending.get_or_insert_with(CatchUpdate::default);
+    update.caught += caught;
+    update.missed += missed;
+    update.nutrition += nutrition;
+    update.score = score;
+    update.finished |= finished;
+  }
+}
+
+/// Runs the falling-pumpkins round and mirrors the simulation onto one small
+/// window per pumpkin. The character stays on the bottom edge and only moves
+/// sideways.
+fn run_catch_round(app: &tauri::AppHandle, lease_id: u64) -> Result<(), String> {
+  const TICK: Duration = Duration::from_millis(16);
+
+  let catch_settings = settings::current_settings().catch_game;
+  let Some(main_window) = app.get_webview_window("main") else {
+    return Ok(());
+  };
+  let window = main_window.as_ref().window();
+  let bounds = walk_bounds(&window);
+  let main_size = main_window.outer_size().map_err(|error| error.to_string())?;
+  let main_pos = main_window.outer_position().map_err(|error| error.to_string())?;
+  let main_w = main_size.width as i32;
+  let main_h = main_size.height as i32;
+  let catcher_y = bounds.bottom() - main_h;
+  let mut catcher_x = main_pos.x.clamp(bounds.x, (bounds.right() - main_w).max(bounds.x));
+
+  let mut sim = CatchSim::new(time_seed());
+  let mut last_tick = Instant::now();
+
+  while !sim.is_over(&catch_settings) {
+    let dt_ms = (last_tick.elapsed().as_millis() as u64).min(50);
+    last_tick = Instant::now();
+
+    let cursor_x = main_window.cursor_position().ok().map(|cursor| cursor.x);
+    let target_x = catch_input_state()
+      .lock()
+      .map(|mut input| input.target_x(catcher_x, main_w, cursor_x))
+      .unwrap_or(catcher_x);
+    catcher_x = CatchSim::catcher_x(&catch_settings, catcher_x, target_x, dt_ms, bounds, main_w);
+    if !move_character(app, lease_id, catcher_x, catcher_y)? {
+      break;
+    }
+
+    let events = sim.step(
+      &catch_settings,
+      dt_ms,
+      bounds,
+      Rect::new(catcher_x, catcher_y, main_w, main_h),
+    );
+    for item in &events.spawned {
+      let rect = item.rect(catch_settings.item_size);
+      let label = catch_item_label(item.id);
+      if let Err(error) = open_food_window(
+        &window,
+        &label,
+        "pumpkin.html?mode=catch",
+        "Pumpkin",
+        rect.x,
+        rect.y,
+        catch_settings.item_size,
+      ) {
+        log::warn!("failed to open {}: {}", label, error);
+      }
+    }
+    for item in sim.items() {
+      if let Some(item_window) = app.get_webview_window(&catch_item_label(item.id)) {
+        let rect = item.rect(catch_settings.item_size);
+        let _ = item_window.set_position(PhysicalPosition::new(rect.x, rect.y));
+      }
+    }
+    for id in events.caught.iter().chain(&events.missed) {
+      if let Some(item_window) = app.get_webview_window(&catch_item_label(*id)) {
+        close_pumpkin_window(&item_window);
+      }
+    }
+    if !events.caught.is_empty() || !events.missed.is_empty() {
+      let caught = events.caught.len() as u32;
+      if caught > 0 {
+        apply_mood(MoodTrigger::Fed {
+          nutrition: caught * catch_settings.nutrition_per_catch,
+        });
+      }
+      queue_catch_update(
+        caught,
+        events.missed.len() as u32,
+        caught * catch_settings.nutrition_per_catch,
+        sim.caught,
+        false,
+      );
+    }
+
+    thread::sleep(TICK);
+  }
+
+  for item in sim.items() {
+    if let Some(item_window) = app.get_webview_window(&catch_item_label(item.id)) {
+      close_pumpkin_window(&item_window);
+    }
+  }
+  apply_mood(MoodTrigger::Play);
+  queue_catch_update(0, 0, 0, sim.caught, true);
+  Ok(())
+}
+
+#[tauri::command]
+fn start_catch_game(window: Window) -> Result<(), String> {
+  let Some(lease_id) = acquire_motion(MotionOwner::Game) else {
+    return Err("Speaki is busy".into());
+  };
+
+  if let Some(pumpkin_window) = window.app_handle().get_webview_window("pumpkin") {
+    close_pumpkin_window(&pumpkin_window);
+    finish_pumpkin_session();
+  }
+  if let Ok(mut input) = catch_input_state().lock() {
+    *input = CatchInput::default();
+  }
+  if let Ok(mut pending) = catch_update_pending_state().lock() {
+    *pending = None;
+  }
+
+  let app = window.app_handle().clone();
+  thread::spawn(move || {
+    if let Err(error) = run_catch_round(&app, lease_id) {
+      log::warn!("catch game stopped: {}", error);
+      queue_catch_update(0, 0, 0, 0, true);
+    }
+    release_motion(lease_id);
+  });
+  Ok(())
+}
+
+#[tauri::command]
+fn set_catch_input(direction: i32) {
+  if let Ok(mut input) = catch_input_state().lock() {
+    input.set_direction(direction);
+  }
+}
+
+#[tauri::command]
+fn take_catch_update() -> Option<CatchUpdate> {
+  catch_update_pending_state()
+    .lock()
+    .ok()
+    .and_then(|mut pending| pending.take())
+}
+
+fn queue_hide_seek_event(event: HideSeekEvent) {
+  if let Ok(mut pending) = hide_seek_events_pending_state().lock() {
+    pending.push(event);
+  }
+}
+
+fn record_hide_seek_round(found: bool) {
+  if let Err(error) = save::update_save(|save| save.hide_seek.record_round(found)) {
+    log::warn!("failed to write hide-and-seek record: {}", error);
+  }
+}
+
+/// Runs `change` against the active hide-and-seek session, if any.
+fn with_hide_seek<T>(change: impl FnOnce(&mut HideSeekSession) -> T) -> Option<T> {
+  hide_seek_state()
+    .lock()
+    .ok()
+    .and_then(|mut session| session.as_mut().map(change))
+}
+
+/// Hides the character at a screen edge each round and waits for the player
+/// to click it. The character comes back to where it started between rounds.
+fn run_hide_seek(app: &tauri::AppHandle, lease_id: u64) -> Result<(), String> {
+  const TICK: Duration = Duration::from_millis(100);
+
+  let hide_settings = settings::current_settings().hide_seek;
+  let Some(main_window) = app.get_webview_window("main") else {
+    return Ok(());
+  };
+  let bounds = walk_bounds(&main_window.as_ref().window());
+  let home = main_window.outer_position().map_err(|error| error.to_string())?;
+  let size = main_window.outer_size().map_err(|error| error.to_string())?;
+
+  loop {
+    let seed = time_seed();
+    let side = HideSide::from_seed(seed.rotate_left(11));
+    let along = (seed >> 8) % 1000;
+    let Some(started) = with_hide_seek(|session| {
+      let now_ms = session.now_ms();
+      session.game.start_round(side, now_ms)
+    })
+    .flatten() else {
+      break;
+    };
+
+    let (hide_x, hide_y) = side.hiding_position(
+      bounds,
+      size.width as i32,
+      size.height as i32,
+      hide_settings.peek_px,
+      along as f64 / 1000.0,
+    );
+    if !move_character(app, lease_id, hide_x, hide_y)? {
+      return Ok(());
+    }
+    queue_hide_seek_event(started);
+
+    loop {
+      thread::sleep(TICK);
+      if !holds_motion(lease_id) {
+        return Ok(());
+      }
+
+      let Some((event, hiding)) = with_hide_seek(|session| {
+        let now_ms = session.now_ms();
+        (session.game.tick(&hide_settings, now_ms), session.game.is_hiding())
+      }) else {
+        return Ok(());
+      };
+      if let Some(event) = event {
+        if matches!(event, HideSeekEvent::Missed { .. }) {
+          record_hide_seek_round(false);
+        }
+        queue_hide_seek_event(event);
+      }
+      if !hiding {
+        break;
+      }
+    }
+
+    if !move_character(app, lease_id, home.x, home.y)? {
+      return Ok(());
+    }
+    thread::sleep(Duration::from_millis(hide_settings.round_break_ms));
+  }
+
+  Ok(())
 }
 
 #[tauri::command]
-async fn spawn_pumpkin(window: Window) -> Result<bool, String> {
-  const PUMPKIN_LABEL: &str = "pumpkin";
-  const PUMPKIN_SIZE: f64 = 220.0;
-
-  set_pumpkin_dragging_state(false);
+fn start_hide_seek(window: Window) -> Result<(), String> {
+  let Some(lease_id) = acquire_motion(MotionOwner::Game) else {
+    return Err("Speaki is busy".into());
+  };
 
-  let app = window.app_handle();
-  if let Some(existing) = app.get_webview_window(PUMPKIN_LABEL) {
-    let _ = existing.close();
+  let hide_settings = settings::current_settings().hide_seek;
+  if let Ok(mut session) = hide_seek_state().lock() {
+    *session = Some(HideSeekSession {
+      game: HideSeekGame::new(&hide_settings),
+      started_at: Instant::now(),
+    });
+  }
+  if let Ok(mut pending) = hide_seek_events_pending_state().lock() {
+    pending.clear();
   }
 
-  let (monitor_x, monitor_y, monitor_width, monitor_height) = monitor_bounds(&window)?;
-
-  let pumpkin_size = PUMPKIN_SIZE.round() as i32;
-  let max_x = monitor_x + (monitor_width as i32 - pumpkin_size).max(0);
-  let max_y = monitor_y + (monitor_height as i32 - pumpkin_size).max(0);
-  let time_seed = SystemTime::now()
-    .duration_since(UNIX_EPOCH)
-    .map(|duration| duration.as_nanos() as u64)
-    .unwrap_or(0);
-  let position_seed = window
-    .outer_position()
-    .map(|position| ((position.x as i64 as u64) << 32) ^ (position.y as i64 as u64))
-    .unwrap_or(0);
+  let app = window.app_handle().clone();
+  thread::spawn(move || {
+    if let Err(error) = run_hide_seek(&app, lease_id) {
+      log::warn!("hide-and-seek stopped: {}", error);
+    }
+    release_motion(lease_id);
+
+    let finished = hide_seek_state()
+      .lock()
+      .ok()
+      .and_then(|mut session| session.take())
+      .map(|session| (session.game.found_count(), session.game.rounds()));
+    if let Some((found, rounds)) = finished {
+      apply_mood(MoodTrigger::Play);
+      queue_hide_seek_event(HideSeekEvent::Finished {
+        found,
+        rounds,
+        record: save::current_save().hide_seek,
+      });
+    }
+  });
+  Ok(())
+}
 
-  let pumpkin_x = random_between(time_seed ^ position_seed ^ 0x9E37_79B9_7F4A_7C15, monitor_x, max_x);
-  let pumpkin_y = random_between(
-    time_seed.rotate_left(17) ^ position_seed ^ 0xC2B2_AE3D_27D4_EB4F,
-    monitor_y,
-    max_y,
-  );
+/// Called when the player clicks the character during hide-and-seek.
+#[tauri::command]
+fn hide_seek_found() -> bool {
+  let Some(event) = with_hide_seek(|session| {
+    let now_ms = session.now_ms();
+    session.game.found(now_ms)
+  })
+  .flatten() else {
+    return false;
+  };
 
-  WebviewWindowBuilder::new(app, PUMPKIN_LABEL, WebviewUrl::App("pumpkin.html".into()))
-    .title("Pumpkin")
-    .position(pumpkin_x as f64, pumpkin_y as f64)
-    .inner_size(PUMPKIN_SIZE, PUMPKIN_SIZE)
-    .resizable(false)
-    .always_on_top(true)
-    .skip_taskbar(true)
-    .decorations(false)
-    .transparent(true)
-    .shadow(false)
-    .focused(false)
-    .build()
-    .map_err(|error| error.to_string())?;
+  record_hide_seek_round(true);
+  queue_hide_seek_event(event);
+  true
+}
 
-  begin_pumpkin_session(window.app_handle().clone());
+#[tauri::command]
+fn take_hide_seek_events() -> Vec<HideSeekEvent> {
+  hide_seek_events_pending_state()
+    .lock()
+    .map(|mut pending| std::mem::take(&mut *pending))
+    .unwrap_or_default()
+}
 
-  let main_size = window.outer_size().map_err(|error| error.to_string())?;
-  let main_width = main_size.width as i32;
-  let main_height = main_size.height as i32;
-  let pumpkin_size = PUMPKIN_SIZE.round() as i32;
-  let gap = 0;
+#[tauri::command]
+fn get_hide_seek_record() -> hide_seek::HideSeekRecord {
+  save::current_save().hide_seek
+}
 
-  let left_candidate = pumpkin_x - main_width - gap;
-  let right_candidate = pumpkin_x + pumpkin_size + gap;
-  let centered_x = pumpkin_x + pumpkin_size / 2 - main_width / 2;
-  let min_main_x = monitor_x;
-  let max_main_x = monitor_x + (monitor_width as i32 - main_width).max(0);
-  let target_x = if left_candidate >= min_main_x {
-    left_candidate
-  } else if right_candidate <= max_main_x {
-    right_candidate
-  } else {
-    clamp_i32(centered_x, min_main_x, max_main_x)
-  };
+fn start_auto_feeder_worker(app: tauri::AppHandle) {
+  const FEEDER_TICK: Duration = Duration::from_secs(20);
 
-  let centered_y = pumpkin_y + pumpkin_size / 2 - main_height / 2;
-  let min_main_y = monitor_y;
-  let max_main_y = monitor_y + (monitor_height as i32 - main_height).max(0);
-  let target_y = clamp_i32(centered_y, min_main_y, max_main_y);
+  thread::spawn(move || {
+    let mut feeder_state = feeder::FeederState::resume(&save::current_save().feeder);
+    loop {
+      thread::sleep(FEEDER_TICK);
+
+      let feeder_settings = settings::current_settings().feeder;
+      let food_present = app.get_webview_window("pumpkin").is_some();
+      let now = chrono::Local::now().naive_local();
+      let pumpkin_index = current_character_state().pumpkin_index;
+      let Some(reason) = feeder_state.decide(&feeder_settings, now, pumpkin_index, food_present) else {
+        continue;
+      };
 
-  start_walk_to_pumpkin_worker(
-    window.clone(),
-    target_x,
-    target_y,
-    main_width,
-    main_height,
-    pumpkin_x,
-    pumpkin_y,
-    PUMPKIN_LABEL,
-  );
+      let Some(main_window) = app.get_webview_window("main") else {
+        continue;
+      };
+      let catalog = food::current_catalog();
+      let food = feeder_settings
+        .food
+        .as_deref()
+        .and_then(|id| catalog.find(id))
+        .or_else(|| catalog.pick_weighted(time_seed()))
+        .cloned();
+      let Some(food) = food else {
+        continue;
+      };
 
-  Ok(false)
+      match spawn_food_window(&main_window.as_ref().window(), food) {
+        Ok(_) => {
+          log::info!("auto-feeder spawned food ({reason:?})");
+          feeder_state.record_spawn(reason, now);
+          let record = feeder_state.record();
+          if let Err(error) = save::update_save(|save| save.feeder = record) {
+            log::warn!("failed to save feeder count: {error}");
+          }
+          if let Ok(mut pending) = auto_feed_pending_state().lock() {
+            *pending = true;
+          }
+        }
+        Err(error) => log::warn!("auto-feeder spawn failed: {error}"),
+      }
+    }
+  });
 }
 
 fn follow_main_toward_pumpkin_windows<R: tauri::Runtime>(
   main_window: &tauri::WebviewWindow<R>,
   pumpkin_window: &tauri::WebviewWindow<R>,
+  lease_id: u64,
 ) -> Result<(), String> {
   const PUMPKIN_GAP: i32 = 0;
   const FOLLOW_RATIO: f64 = 0.18;
-  const MAX_STEP: i32 = 8;
+  const BASE_MAX_STEP: f64 = 8.0;
 
   let pumpkin_pos = pumpkin_window.outer_position().map_err(|error| error.to_string())?;
   let pumpkin_size = pumpkin_window.outer_size().map_err(|error| error.to_string())?;
@@ -647,11 +2621,11 @@ fn follow_main_toward_pumpkin_windows<R: tauri::Runtime>(
   let main_h = main_size.height as i32;
 
   if is_pumpkin_chase_timed_out() {
-    let _ = close_dragging_pumpkin_if_touching(pumpkin_window, main_pos.x, main_pos.y, main_w, main_h);
+    let _ = eat_dragging_pumpkin_if_touching(pumpkin_window, main_pos.x, main_pos.y, main_w, main_h);
     return Ok(());
   }
 
-  if close_dragging_pumpkin_if_touching(pumpkin_window, main_pos.x, main_pos.y, main_w, main_h) {
+  if eat_dragging_pumpkin_if_touching(pumpkin_window, main_pos.x, main_pos.y, main_w, main_h) {
     return Ok(());
   }
 
@@ -693,7 +2667,7 @@ fn follow_main_toward_pumpkin_windows<R: tauri::Runtime>(
   let delta_x = target_x - main_pos.x;
   let delta_y = target_y - main_pos.y;
   if delta_x.abs() <= 1 && delta_y.abs() <= 1 {
-    let _ = close_dragging_pumpkin_if_touching(pumpkin_window, main_pos.x, main_pos.y, main_w, main_h);
+    let _ = eat_dragging_pumpkin_if_touching(pumpkin_window, main_pos.x, main_pos.y, main_w, main_h);
     return Ok(());
   }
 
@@ -705,8 +2679,10 @@ fn follow_main_toward_pumpkin_windows<R: tauri::Runtime>(
   if step_y == 0 && delta_y != 0 {
     step_y = delta_y.signum();
   }
-  step_x = clamp_i32(step_x, -MAX_STEP, MAX_STEP);
-  step_y = clamp_i32(step_y, -MAX_STEP, MAX_STEP);
+  let speed_factor = current_chase_gait().unwrap_or_default().speed_factor();
+  let max_step = (BASE_MAX_STEP * speed_factor).round().max(1.0) as i32;
+  step_x = clamp_i32(step_x, -max_step, max_step);
+  step_y = clamp_i32(step_y, -max_step, max_step);
 
   let bob = if let Ok(mut phase) = follow_phase_state().lock() {
     *phase = (*phase + 1) % 4;
@@ -721,11 +2697,11 @@ fn follow_main_toward_pumpkin_windows<R: tauri::Runtime>(
 
   let next_x = clamp_i32(main_pos.x + step_x, min_main_x, max_main_x);
   let next_y = clamp_i32(main_pos.y + step_y + bob, min_main_y, max_main_y);
-  main_window
-    .set_position(PhysicalPosition::new(next_x, next_y))
-    .map_err(|error| error.to_string())?;
+  if !move_character(main_window.app_handle(), lease_id, next_x, next_y)? {
+    return Ok(());
+  }
 
-  let _ = close_dragging_pumpkin_if_touching(pumpkin_window, next_x, next_y, main_w, main_h);
+  let _ = eat_dragging_pumpkin_if_touching(pumpkin_window, next_x, next_y, main_w, main_h);
   Ok(())
 }
 
@@ -744,8 +2720,8 @@ fn stop_pumpkin_drag() {
 }
 
 #[tauri::command]
-fn take_pumpkin_eaten_flag() -> bool {
-  take_pumpkin_eaten_pending()
+fn take_pumpkin_bites() -> Vec<PumpkinBite> {
+  take_pumpkin_bites_pending()
 }
 
 #[tauri::command]
@@ -753,8 +2729,392 @@ fn take_pumpkin_timeout_flag() -> bool {
   take_pumpkin_timeout_pending()
 }
 
+#[tauri::command]
+fn take_auto_feed_flag() -> bool {
+  if let Ok(mut pending) = auto_feed_pending_state().lock() {
+    let was_pending = *pending;
+    *pending = false;
+    return was_pending;
+  }
+  false
+}
+
+#[tauri::command]
+fn take_pumpkin_stashed() -> Option<u32> {
+  pumpkin_stashed_pending_state()
+    .lock()
+    .ok()
+    .and_then(|mut pending| pending.take())
+}
+
+#[tauri::command]
+fn get_stash_count() -> u32 {
+  save::current_save().stash.len() as u32
+}
+
+#[tauri::command]
+async fn spawn_from_stash(window: Window) -> Result<bool, String> {
+  let Some(food_id) = save::update_save(|save| save.stash.pop())? else {
+    return Err("stash is empty".into());
+  };
+
+  let catalog = food::current_catalog();
+  let Some(food) = catalog.find(&food_id).or_else(|| catalog.foods.first()).cloned() else {
+    return Err("food catalog is empty".into());
+  };
+  spawn_food_window(&window, food).inspect_err(|_| {
+    let _ = save::update_save(|save| save.stash.push(food_id));
+  })
+}
+
+#[tauri::command]
+fn take_pumpkin_stage_change() -> Option<PumpkinStage> {
+  take_pumpkin_stage_pending()
+}
+
+#[tauri::command]
+fn report_character_state(sulking: bool) {
+  if let Ok(mut state) = character_sulking_state().lock() {
+    *state = sulking;
+  }
+}
+
+/// Classifies pointer input from the character window. `now_ms` is on the
+/// same clock as the samples and resolves click bursts that have ended.
+#[tauri::command]
+fn report_pointer_samples(samples: Vec<PointerSample>, now_ms: u64) -> Vec<Gesture> {
+  let gesture_settings = settings::current_settings().gesture;
+  let gestures: Vec<Gesture> = {
+    let Ok(mut state) = gesture_state().lock() else {
+      return Vec::new();
+    };
+    let mut gestures: Vec<Gesture> = samples
+      .into_iter()
+      .filter_map(|sample| state.recognizer.feed(&gesture_settings, sample))
+      .collect();
+    gestures.extend(state.recognizer.flush(&gesture_settings, now_ms));
+    gestures
+  };
+
+  for gesture in &gestures {
+    react_to_gesture(*gesture);
+  }
+  gestures
+}
+
+#[tauri::command]
+fn get_journal() -> Vec<journal::JournalEntry> {
+  save::current_save().journal
+}
+
+#[tauri::command]
+fn get_mood() -> MoodSnapshot {
+  mood_snapshot()
+}
+
+#[tauri::command]
+fn apply_mood_trigger(trigger: MoodTrigger) -> MoodSnapshot {
+  if matches!(trigger, MoodTrigger::Click | MoodTrigger::Drag) {
+    note_interaction();
+  }
+  if matches!(trigger, MoodTrigger::Click) {
+    record_care(Care::Pet);
+    queue_script_event(ScriptEvent::Clicked);
+  }
+  apply_mood(trigger);
+  mood_snapshot()
+}
+
+/// Seeds the pumpkin index from the value the frontend kept in local storage
+/// before needs moved to the save file. Only honoured once, on a save that
+/// has no needs yet.
+#[tauri::command]
+fn import_legacy_pumpkin_index(pumpkin_index: u32) -> MoodSnapshot {
+  let allowed = legacy_hunger_import_state()
+    .lock()
+    .map(|mut allowed| std::mem::replace(&mut *allowed, false))
+    .unwrap_or(false);
+  if allowed {
+    if let Ok(mut engine) = mood_state().lock() {
+      engine.needs.set(mood::Need::Hunger, pumpkin_index.min(100) as f64);
+    }
+    persist_progress();
+  }
+  mood_snapshot()
+}
+
+enum AudioMessage {
+  Play(VoiceRequest, mpsc::Sender<Result<PlayOutcome, String>>),
+  StopAll,
+}
+
+fn audio_state() -> &'static Mutex<Option<mpsc::Sender<AudioMessage>>> {
+  AUDIO.get_or_init(|| Mutex::new(None))
+}
+
+fn voice_clips_state() -> &'static Mutex<HashMap<String, Arc<[u8]>>> {
+  VOICE_CLIPS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Owns the output device, which has to stay on one thread, and plays what
+/// the commands send it. Falls back to silence when there is no device.
+fn start_audio_worker() {
+  const TICK: Duration = Duration::from_millis(100);
+
+  let (sender, receiver) = mpsc::channel();
+  if let Ok(mut audio) = audio_state().lock() {
+    *audio = Some(sender);
+  }
+  thread::spawn(move || {
+    let backend: Box<dyn audio::AudioBackend> = match RodioBackend::open() {
+      Ok(backend) => Box::new(backend),
+      Err(error) => {
+        log::warn!("no audio output, voices are muted: {}", error);
+        Box::new(NullBackend)
+      }
+    };
+    let mut mixer = Mixer::new(backend);
+    loop {
+      match receiver.recv_timeout(TICK) {
+        Ok(AudioMessage::Play(request, reply)) => {
+          let outcome = mixer.play(&settings::current_settings().audio, request);
+          let _ = reply.send(outcome);
+        }
+        Ok(AudioMessage::StopAll) => mixer.stop_all(),
+        Err(mpsc::RecvTimeoutError::Timeout) => mixer.reap(),
+        Err(mpsc::RecvTimeoutError::Disconnected) => return,
+      }
+    }
+  });
+}
+
+fn send_audio(message: AudioMessage) -> Result<(), String> {
+  let audio = audio_state().lock().map_err(|error| error.to_string())?;
+  let sender = audio.as_ref().ok_or("audio is not running")?;
+  sender.send(message).map_err(|error| error.to_string())
+}
+
+/// A voice clip's bytes, from the active character pack when it has the clip
+/// and from the bundled frontend assets otherwise.
+fn voice_clip(app: &tauri::AppHandle, voice: &str) -> Result<Arc<[u8]>, String> {
+  if !character::is_plain_file_name(voice) {
+    return Err(format!("`{voice}` is not a voice file name"));
+  }
+  let pack = character::active_pack(&settings::current_settings().character);
+  let file = format!("voice/{voice}");
+  let from_pack = !matches!(pack.source, PackSource::BuiltIn) && pack.manifest.referenced_files().contains(&file);
+  let key = if from_pack {
+    format!("{}/{file}", pack.manifest.id)
+  } else {
+    file.clone()
+  };
+  if let Some(bytes) = voice_clips_state().lock().ok().and_then(|clips| clips.get(&key).cloned()) {
+    return Ok(bytes);
+  }
+
+  let bytes: Arc<[u8]> = if from_pack {
+    pack.source.read(&file)?.into()
+  } else {
+    let asset = app.asset_resolver().get(file.clone()).ok_or_else(|| format!("no bundled {file}"))?;
+    asset.bytes().into()
+  };
+  if let Ok(mut clips) = voice_clips_state().lock() {
+    clips.insert(key, Arc::clone(&bytes));
+  }
+  Ok(bytes)
+}
+
+/// Loading the clip may read a pack zip and the mixer answers from its own
+/// thread, so both happen on a blocking thread, never on the main one.
+#[tauri::command]
+async fn play_voice(
+  window: Window,
+  voice: String,
+  category: Option<AudioCategory>,
+  pan: Option<f32>,
+) -> Result<PlayOutcome, String> {
+  let app = window.app_handle().clone();
+  tauri::async_runtime::spawn_blocking(move || {
+    let bytes = voice_clip(&app, &voice)?;
+    let gain = character::active_pack(&settings::current_settings().character)
+      .manifest
+      .personality
+      .voice_volume as f32;
+    let request = VoiceRequest {
+      clip: voice,
+      bytes,
+      category: category.unwrap_or_default(),
+      pan: pan.unwrap_or(0.0),
+      gain,
+    };
+    let (reply, outcome) = mpsc::channel();
+    send_audio(AudioMessage::Play(request, reply))?;
+    outcome.recv().map_err(|error| error.to_string())?
+  })
+  .await
+  .map_err(|error| error.to_string())?
+}
+
+#[tauri::command]
+fn stop_voices() -> Result<(), String> {
+  send_audio(AudioMessage::StopAll)
+}
+
+/// The selector and the dice it rolls, seeded once so picks made in quick
+/// succession do not share a seed.
+fn voice_selector_state() -> &'static Mutex<(VoiceSelector, Dice)> {
+  VOICE_SELECTOR.get_or_init(|| Mutex::new((VoiceSelector::default(), Dice::new(time_seed()))))
+}
+
+/// Picks a clip from a pack pool (`drag`, `satiated_click`, `expression:<id>`)
+/// or from `mood`, the current mood's voices, using the pack's weights,
+/// cooldowns and repeat history.
+#[tauri::command]
+fn pick_voice(pool: String) -> Option<String> {
+  let manifest = character::active_pack(&settings::current_settings().character).manifest;
+  let snapshot = mood_snapshot();
+  let clips = if pool == "mood" {
+    let mut voices = snapshot.voices;
+    if snapshot.satisfied {
+      voices.extend(snapshot.bond.voices);
+    }
+    voices
+  } else {
+    manifest.voice_pool(&pool)?.to_vec()
+  };
+
+  let mut state = voice_selector_state().lock().ok()?;
+  let (selector, dice) = &mut *state;
+  selector.pick(&manifest.voice_selection, &clips, snapshot.mood, uptime_ms(), dice)
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct ActivePack {
+  manifest: CharacterManifest,
+  /// Where the pack's `images/` and `voice/` folders are served; `None` for
+  /// the built-in pack, which ships with the frontend.
+  asset_base: Option<String>,
+}
+
+fn pack_asset_base(id: &str) -> String {
+  if cfg!(windows) {
+    format!("http://pack.localhost/{id}/")
+  } else {
+    format!("pack://localhost/{id}/")
+  }
+}
+
+#[tauri::command]
+fn get_character_pack() -> ActivePack {
+  let pack = character::active_pack(&settings::current_settings().character);
+  let asset_base = match pack.source {
+    PackSource::BuiltIn => None,
+    PackSource::Dir(_) | PackSource::Zip(_) => Some(pack_asset_base(&pack.manifest.id)),
+  };
+  ActivePack {
+    manifest: pack.manifest,
+    asset_base,
+  }
+}
+
+#[tauri::command]
+fn list_character_packs() -> Vec<PackSummary> {
+  character::list_packs()
+}
+
+/// The images and voice clips bundled with the app, as described at build time.
+#[tauri::command]
+fn list_assets() -> Vec<AssetEntry> {
+  assets::bundled_assets().to_vec()
+}
+
+/// Checks a character pack folder or `.zip` and installs it into the app data
+/// dir. Unpacking and decoding run on a blocking thread, off the main thread.
+#[tauri::command]
+async fn import_character_pack(window: Window, path: String) -> Result<ImportReport, String> {
+  let data_dir = window.app_handle().path().app_data_dir().map_err(|error| error.to_string())?;
+  tauri::async_runtime::spawn_blocking(move || pack_import::import_pack(Path::new(&path), &data_dir))
+    .await
+    .map_err(|error| error.to_string())
+}
+
+/// Where Tauri puts the app data dir, for the CLI, which runs without an app.
+fn cli_data_dir() -> Option<PathBuf> {
+  let base = if cfg!(windows) {
+    PathBuf::from(std::env::var_os("APPDATA")?)
+  } else if cfg!(target_os = "macos") {
+    PathBuf::from(std::env::var_os("HOME")?).join("Library/Application Support")
+  } else {
+    match std::env::var_os("XDG_DATA_HOME") {
+      Some(dir) => PathBuf::from(dir),
+      None => PathBuf::from(std::env::var_os("HOME")?).join(".local/share"),
+    }
+  };
+  Some(base.join(APP_IDENTIFIER))
+}
+
+/// `import-pack <path> [--data-dir <dir>]`: installs a character pack without
+/// starting the widget. Returns the process exit code.
+pub fn import_pack_cli(args: &[String]) -> i32 {
+  let mut source = None;
+  let mut data_dir = None;
+  let mut args = args.iter();
+  while let Some(arg) = args.next() {
+    match arg.as_str() {
+      "--data-dir" => data_dir = args.next().map(PathBuf::from),
+      _ if source.is_none() => source = Some(PathBuf::from(arg)),
+      _ => {
+        eprintln!("unexpected argument `{arg}`");
+        return 2;
+      }
+    }
+  }
+  let Some(source) = source else {
+    eprintln!("usage: import-pack <pack folder or .zip> [--data-dir <dir>]");
+    return 2;
+  };
+  let Some(data_dir) = data_dir.or_else(cli_data_dir) else {
+    eprintln!("cannot find the app data dir; pass --data-dir");
+    return 2;
+  };
+
+  let report = pack_import::import_pack(&source, &data_dir);
+  println!("{report}");
+  if report.installed_to.is_some() {
+    0
+  } else {
+    1
+  }
+}
+
+/// Serves `pack://localhost/<pack id>/<images|voice>/<file>` from installed packs.
+fn serve_pack_asset(request: tauri::http::Request<Vec<u8>>) -> tauri::http::Response<Vec<u8>> {
+  let found = character::parse_asset_path(request.uri().path()).and_then(|(id, file)| {
+    let pack = character::find_pack(&id)?;
+    let bytes = pack.source.read(&file).ok()?;
+    Some((character::content_type(&file), bytes))
+  });
+  let builder = tauri::http::Response::builder().header("Access-Control-Allow-Origin", "*");
+  let response = match found {
+    Some((content_type, bytes)) => builder.header("Content-Type", content_type).body(bytes),
+    None => builder.status(404).body(Vec::new()),
+  };
+  response.unwrap_or_default()
+}
+
+#[tauri::command]
+fn get_settings() -> settings::Settings {
+  settings::current_settings()
+}
+
+#[tauri::command]
+fn set_settings(settings: settings::Settings) -> Result<(), String> {
+  settings::replace_settings(settings)
+}
+
 #[tauri::command]
 fn exit_app(window: Window) -> Result<(), String> {
+  persist_progress();
   window.app_handle().exit(0);
   Ok(())
 }
@@ -766,17 +3126,94 @@ pub fn run() {
       start_drag,
       get_window_geometry,
       set_window_position,
-      spawn_pumpkin,
+      spawn_food,
+      get_current_food,
       start_pumpkin_drag,
       stop_pumpkin_drag,
-      take_pumpkin_eaten_flag,
+      take_pumpkin_bites,
       take_pumpkin_timeout_flag,
+      take_pumpkin_stage_change,
+      take_auto_feed_flag,
+      take_pumpkin_stashed,
+      get_stash_count,
+      spawn_from_stash,
+      start_flee_game,
+      take_flee_result,
+      get_flee_record,
+      start_catch_game,
+      set_catch_input,
+      take_catch_update,
+      start_hide_seek,
+      hide_seek_found,
+      take_hide_seek_events,
+      get_hide_seek_record,
+      report_character_state,
+      get_mood,
+      get_journal,
+      report_pointer_samples,
+      throw_character,
+      take_throw_events,
+      take_cursor_events,
+      plan_activities,
+      take_nap,
+      seek_pumpkin,
+      take_script_commands,
+      get_character_pack,
+      list_character_packs,
+      import_character_pack,
+      list_assets,
+      play_voice,
+      stop_voices,
+      pick_voice,
+      apply_mood_trigger,
+      import_legacy_pumpkin_index,
+      get_settings,
+      set_settings,
       exit_app
     ])
+    .register_uri_scheme_protocol("pack", |_context, request| serve_pack_asset(request))
     .on_page_load(|window, payload| {
       println!("[speaki] loaded url: {} on {}", payload.url(), window.label());
     })
     .setup(|app| {
+      if let Ok(config_dir) = app.path().app_config_dir() {
+        settings::load_settings(&config_dir);
+        food::load_catalog(&config_dir);
+        behavior::load_tree(&config_dir);
+        let scripts_dir = config_dir.join(scripting::SCRIPTS_DIR_NAME);
+        if let Err(error) = std::fs::create_dir_all(&scripts_dir) {
+          log::warn!("failed to create {}: {}", scripts_dir.display(), error);
+        }
+        start_script_worker(app.handle().clone(), scripts_dir);
+      }
+      if let Ok(data_dir) = app.path().app_data_dir() {
+        save::load_save(&data_dir);
+        character::load_packs(&data_dir);
+      }
+      let loaded = save::current_save();
+      if let Ok(mut sickness) = sickness_state().lock() {
+        *sickness = loaded.sickness;
+      }
+      if let Ok(mut bond) = bond_state().lock() {
+        *bond = loaded.bond.clone();
+        bond.catch_up(&settings::current_settings().bond, chrono::Local::now().naive_local());
+      }
+      match loaded.needs {
+        Some(needs) => {
+          if let Ok(mut engine) = mood_state().lock() {
+            *engine = MoodEngine::new(needs);
+          }
+        }
+        None => {
+          if let Ok(mut allowed) = legacy_hunger_import_state().lock() {
+            *allowed = true;
+          }
+        }
+      }
+      start_mood_worker();
+      start_audio_worker();
+      start_auto_feeder_worker(app.handle().clone());
+      start_cursor_worker(app.handle().clone());
       if cfg!(debug_assertions) {
         app.handle().plugin(
           tauri_plugin_log::Builder::default()
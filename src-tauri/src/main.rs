@@ -2,5 +2,9 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 fn main() {
+  let args: Vec<String> = std::env::args().skip(1).collect();
+  if args.first().is_some_and(|command| command == "import-pack") {
+    std::process::exit(app_lib::import_pack_cli(&args[1..]));
+  }
   app_lib::run();
 }
@@ -0,0 +1,139 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EatingSettings {
+  pub bites: u32,
+  pub bite_interval_ms: u64,
+  /// Window size, in percent of the original, just before the last bite.
+  pub min_scale_percent: u32,
+}
+
+impl Default for EatingSettings {
+  fn default() -> Self {
+    Self {
+      bites: 4,
+      bite_interval_ms: 420,
+      min_scale_percent: 35,
+    }
+  }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct BiteOutcome {
+  pub nutrition: u32,
+  pub bites_taken: u32,
+  pub remaining_portion: f64,
+  pub finished: bool,
+}
+
+/// Tracks how much of the current food is left across interrupted meals.
+#[derive(Clone, Copy, Debug)]
+pub struct MealProgress {
+  total_bites: u32,
+  bites_taken: u32,
+  credited_nutrition: u32,
+}
+
+impl MealProgress {
+  pub fn new(total_bites: u32) -> Self {
+    Self {
+      total_bites: total_bites.max(1),
+      bites_taken: 0,
+      credited_nutrition: 0,
+    }
+  }
+
+  pub fn remaining_portion(&self) -> f64 {
+    1.0 - self.bites_taken as f64 / self.total_bites as f64
+  }
+
+  /// Takes one bite and returns the nutrition it is worth. Per-bite values are
+  /// derived from the running total so a finished meal sums to `full_nutrition`.
+  pub fn take_bite(&mut self, full_nutrition: u32) -> Option<BiteOutcome> {
+    if self.bites_taken >= self.total_bites {
+      return None;
+    }
+
+    self.bites_taken += 1;
+    let eaten_portion = self.bites_taken as f64 / self.total_bites as f64;
+    let owed = (full_nutrition as f64 * eaten_portion).round() as u32;
+    let nutrition = owed.saturating_sub(self.credited_nutrition);
+    self.credited_nutrition = self.credited_nutrition.max(owed);
+
+    Some(BiteOutcome {
+      nutrition,
+      bites_taken: self.bites_taken,
+      remaining_portion: self.remaining_portion(),
+      finished: self.bites_taken >= self.total_bites,
+    })
+  }
+
+  /// Scale for the food window given what is left of it.
+  pub fn window_scale(&self, settings: &EatingSettings) -> f64 {
+    let min_scale = (settings.min_scale_percent.min(100) as f64) / 100.0;
+    let remaining = self.remaining_portion();
+    if remaining >= 1.0 {
+      return 1.0;
+    }
+    let last_bite_remaining = 1.0 / self.total_bites as f64;
+    let t = ((remaining - last_bite_remaining) / (1.0 - last_bite_remaining)).clamp(0.0, 1.0);
+    min_scale + (1.0 - min_scale) * t
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn bites_add_up_to_the_full_nutrition() {
+    let mut meal = MealProgress::new(4);
+    let mut nutrition = Vec::new();
+    while let Some(bite) = meal.take_bite(10) {
+      nutrition.push(bite.nutrition);
+      assert_eq!(bite.finished, bite.bites_taken == 4);
+    }
+
+    assert_eq!(nutrition, vec![3, 2, 3, 2]);
+    assert_eq!(meal.remaining_portion(), 0.0);
+    assert!(meal.take_bite(10).is_none());
+  }
+
+  #[test]
+  fn an_interrupted_meal_resumes_where_it_stopped() {
+    let mut meal = MealProgress::new(4);
+    meal.take_bite(10);
+    meal.take_bite(10);
+    assert_eq!(meal.remaining_portion(), 0.5);
+
+    let bite = meal.take_bite(10).unwrap();
+    assert_eq!(bite.bites_taken, 3);
+    assert_eq!(bite.remaining_portion, 0.25);
+    assert!(!bite.finished);
+  }
+
+  #[test]
+  fn the_window_shrinks_to_the_minimum_before_the_last_bite() {
+    let settings = EatingSettings::default();
+    let mut meal = MealProgress::new(settings.bites);
+    assert_eq!(meal.window_scale(&settings), 1.0);
+
+    let mut previous = 1.0;
+    for _ in 0..3 {
+      meal.take_bite(10);
+      let scale = meal.window_scale(&settings);
+      assert!(scale < previous);
+      previous = scale;
+    }
+    assert!((previous - 0.35).abs() < 1e-9);
+  }
+
+  #[test]
+  fn zero_bites_still_takes_one() {
+    let mut meal = MealProgress::new(0);
+    let bite = meal.take_bite(7).unwrap();
+    assert_eq!(bite.nutrition, 7);
+    assert!(bite.finished);
+  }
+}
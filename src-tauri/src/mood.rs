@@ -0,0 +1,497 @@
+use crate::{
+  clock,
+  decay::{DecayCurve, DecaySettings},
+  sleep::SleepSettings,
+};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+/// Each need is a 0–100 satisfaction level where 100 is fully satisfied, so a
+/// low `fun` means a bored character. `hunger` is the pumpkin index.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Need {
+  Hunger,
+  Affection,
+  Energy,
+  Fun,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Needs {
+  pub hunger: f64,
+  pub affection: f64,
+  pub energy: f64,
+  pub fun: f64,
+}
+
+impl Default for Needs {
+  fn default() -> Self {
+    Self {
+      hunger: 100.0,
+      affection: 70.0,
+      energy: 100.0,
+      fun: 80.0,
+    }
+  }
+}
+
+impl Needs {
+  fn slot(&mut self, need: Need) -> &mut f64 {
+    match need {
+      Need::Hunger => &mut self.hunger,
+      Need::Affection => &mut self.affection,
+      Need::Energy => &mut self.energy,
+      Need::Fun => &mut self.fun,
+    }
+  }
+
+  pub fn adjust(&mut self, need: Need, delta: f64) {
+    let slot = self.slot(need);
+    *slot = (*slot + delta).clamp(0.0, 100.0);
+  }
+
+  pub fn set(&mut self, need: Need, value: f64) {
+    *self.slot(need) = value.clamp(0.0, 100.0);
+  }
+}
+
+/// How the needs other than hunger, which follows `DecaySettings::hunger`,
+/// drain while nothing happens. Energy also recovers by
+/// `energy_recovery_per_minute` while the character rests.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NeedDrift {
+  pub affection: DecayCurve,
+  pub energy: DecayCurve,
+  pub fun: DecayCurve,
+  pub energy_recovery_per_minute: f64,
+}
+
+impl Default for NeedDrift {
+  fn default() -> Self {
+    Self {
+      affection: DecayCurve::Linear { per_minute: 0.5 },
+      energy: DecayCurve::Linear { per_minute: 0.0 },
+      fun: DecayCurve::Linear { per_minute: 2.0 },
+      energy_recovery_per_minute: 0.5,
+    }
+  }
+}
+
+/// What a trigger does to the needs.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TriggerEffects {
+  pub click_affection: f64,
+  pub click_fun: f64,
+  pub drag_affection: f64,
+  pub fed_affection: f64,
+  pub wander_energy_per_100px: f64,
+  pub wander_fun_per_100px: f64,
+  pub play_fun: f64,
+  pub play_energy: f64,
+  pub stroke_affection: f64,
+  pub double_click_affection: f64,
+  pub double_click_fun: f64,
+  /// Being poked over and over is annoying.
+  pub click_streak_affection: f64,
+  pub shake_affection: f64,
+  pub shake_energy: f64,
+}
+
+impl Default for TriggerEffects {
+  fn default() -> Self {
+    Self {
+      click_affection: 3.0,
+      click_fun: 2.0,
+      drag_affection: -5.0,
+      fed_affection: 2.0,
+      wander_energy_per_100px: -0.4,
+      wander_fun_per_100px: 0.5,
+      play_fun: 20.0,
+      play_energy: -6.0,
+      stroke_affection: 6.0,
+      double_click_affection: 1.0,
+      double_click_fun: 4.0,
+      click_streak_affection: -3.0,
+      shake_affection: -6.0,
+      shake_energy: -8.0,
+    }
+  }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum MoodTrigger {
+  Fed { nutrition: u32 },
+  FullRefill,
+  Click,
+  Drag,
+  Wander { distance_px: f64 },
+  Play,
+  Stroke,
+  DoubleClick,
+  ClickStreak,
+  Shake,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Mood {
+  Starving,
+  Hungry,
+  Peckish,
+  Tired,
+  Lonely,
+  Bored,
+  Happy,
+  Content,
+}
+
+impl Mood {
+  /// Moods in which the character idles and shuffles its happy expressions.
+  pub fn is_satisfied(self) -> bool {
+    matches!(self, Mood::Happy | Mood::Content)
+  }
+}
+
+/// Expression id (from the frontend's expression table) and voice files used
+/// while in a mood.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MoodProfile {
+  pub mood: Mood,
+  pub expression: String,
+  pub voices: Vec<String>,
+}
+
+fn profile(mood: Mood, expression: &str, voices: &[&str]) -> MoodProfile {
+  MoodProfile {
+    mood,
+    expression: expression.into(),
+    voices: voices.iter().map(|voice| voice.to_string()).collect(),
+  }
+}
+
+fn default_profiles() -> Vec<MoodProfile> {
+  vec![
+    profile(Mood::Starving, "crying3", &["don't push.WAV", "eww.WAV"]),
+    profile(Mood::Hungry, "crying", &["don't push.WAV", "eww2.WAV"]),
+    profile(Mood::Peckish, "crying2", &["don't push.WAV"]),
+    profile(Mood::Tired, "crying3", &["clean.WAV", "eww.WAV"]),
+    profile(Mood::Lonely, "crying2", &["speaki.WAV", "kakurenbo.WAV"]),
+    profile(Mood::Bored, "back", &["kakurenbo.WAV", "speaki.WAV"]),
+    profile(Mood::Happy, "smile2", &["like2.WAV", "like3.WAV", "clean.WAV"]),
+    profile(
+      Mood::Content,
+      "smile",
+      &[
+        "clean.WAV",
+        "kakurenbo.WAV",
+        "like.WAV",
+        "like2.WAV",
+        "like3.WAV",
+        "speaki.WAV",
+      ],
+    ),
+  ]
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MoodSettings {
+  pub decay: DecaySettings,
+  pub drift: NeedDrift,
+  pub triggers: TriggerEffects,
+  pub profiles: Vec<MoodProfile>,
+}
+
+impl Default for MoodSettings {
+  fn default() -> Self {
+    Self {
+      decay: DecaySettings::default(),
+      drift: NeedDrift::default(),
+      triggers: TriggerEffects::default(),
+      profiles: default_profiles(),
+    }
+  }
+}
+
+impl MoodSettings {
+  /// The configured profile for `mood`, falling back to the built-in one.
+  pub fn profile(&self, mood: Mood) -> MoodProfile {
+    self
+      .profiles
+      .iter()
+      .find(|profile| profile.mood == mood)
+      .cloned()
+      .or_else(|| default_profiles().into_iter().find(|profile| profile.mood == mood))
+      .unwrap_or_else(|| profile(mood, "smile", &[]))
+  }
+}
+
+/// Applies drift and triggers to the needs and derives the current mood.
+#[derive(Clone, Debug, Default)]
+pub struct MoodEngine {
+  pub needs: Needs,
+}
+
+impl MoodEngine {
+  pub fn new(needs: Needs) -> Self {
+    Self { needs }
+  }
+
+  /// Advances the needs by `elapsed_ms` ending at `now`. The caller owns the
+  /// clock, so the whole model can be driven by a virtual one. `sleep` is set
+  /// while the character is asleep.
+  pub fn tick(
+    &mut self,
+    settings: &MoodSettings,
+    now: NaiveDateTime,
+    elapsed_ms: u64,
+    away: bool,
+    sleep: Option<&SleepSettings>,
+  ) {
+    let decay = &settings.decay;
+    if away && decay.pause_when_away {
+      return;
+    }
+
+    let minutes = elapsed_ms as f64 / 60_000.0;
+    let mut multiplier = decay.difficulty.drain_multiplier();
+    if let Some(sleep) = sleep {
+      multiplier *= sleep.decay_multiplier.max(0.0);
+      self.needs.adjust(Need::Energy, sleep.energy_per_minute * minutes);
+    }
+    let minute_of_day = clock::minute_of_day(now);
+    let hunger_loss = decay.hunger.decay(self.needs.hunger, minutes, minute_of_day);
+    self.needs.adjust(Need::Hunger, -hunger_loss * multiplier);
+
+    let drift = &settings.drift;
+    for (need, curve) in [
+      (Need::Affection, &drift.affection),
+      (Need::Energy, &drift.energy),
+      (Need::Fun, &drift.fun),
+    ] {
+      let loss = curve.decay(*self.needs.slot(need), minutes, minute_of_day);
+      self.needs.adjust(need, -loss * multiplier);
+    }
+    self
+      .needs
+      .adjust(Need::Energy, drift.energy_recovery_per_minute * minutes);
+  }
+
+  pub fn apply(&mut self, settings: &MoodSettings, trigger: MoodTrigger) {
+    let effects = &settings.triggers;
+    match trigger {
+      MoodTrigger::Fed { nutrition } => {
+        self.needs.adjust(Need::Hunger, nutrition as f64);
+        self.needs.adjust(Need::Affection, effects.fed_affection);
+      }
+      MoodTrigger::FullRefill => {
+        self.needs.set(Need::Hunger, 100.0);
+        self.needs.adjust(Need::Affection, effects.fed_affection);
+      }
+      MoodTrigger::Click => {
+        self.needs.adjust(Need::Affection, effects.click_affection);
+        self.needs.adjust(Need::Fun, effects.click_fun);
+      }
+      MoodTrigger::Drag => self.needs.adjust(Need::Affection, effects.drag_affection),
+      MoodTrigger::Wander { distance_px } => {
+        let hundreds = distance_px.max(0.0) / 100.0;
+        self
+          .needs
+          .adjust(Need::Energy, effects.wander_energy_per_100px * hundreds);
+        self
+          .needs
+          .adjust(Need::Fun, effects.wander_fun_per_100px * hundreds);
+      }
+      MoodTrigger::Play => {
+        self.needs.adjust(Need::Fun, effects.play_fun);
+        self.needs.adjust(Need::Energy, effects.play_energy);
+      }
+      MoodTrigger::Stroke => self.needs.adjust(Need::Affection, effects.stroke_affection),
+      MoodTrigger::DoubleClick => {
+        self.needs.adjust(Need::Affection, effects.double_click_affection);
+        self.needs.adjust(Need::Fun, effects.double_click_fun);
+      }
+      MoodTrigger::ClickStreak => self.needs.adjust(Need::Affection, effects.click_streak_affection),
+      MoodTrigger::Shake => {
+        self.needs.adjust(Need::Affection, effects.shake_affection);
+        self.needs.adjust(Need::Energy, effects.shake_energy);
+      }
+    }
+  }
+
+  /// Hunger thresholds match the old pumpkin index milestones (50/30/10) and
+  /// win over everything else; the other needs only show once fed.
+  pub fn mood(&self) -> Mood {
+    let needs = &self.needs;
+    if needs.hunger <= 10.0 {
+      Mood::Starving
+    } else if needs.hunger <= 30.0 {
+      Mood::Hungry
+    } else if needs.hunger <= 50.0 {
+      Mood::Peckish
+    } else if needs.energy <= 20.0 {
+      Mood::Tired
+    } else if needs.affection <= 25.0 {
+      Mood::Lonely
+    } else if needs.fun <= 25.0 {
+      Mood::Bored
+    } else if [needs.hunger, needs.affection, needs.energy, needs.fun]
+      .iter()
+      .all(|value| *value >= 70.0)
+    {
+      Mood::Happy
+    } else {
+      Mood::Content
+    }
+  }
+
+  /// The pumpkin index as the frontend and gait curve know it.
+  pub fn pumpkin_index(&self) -> u32 {
+    self.needs.hunger.round().clamp(0.0, 100.0) as u32
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::decay::Difficulty;
+
+  fn noon() -> NaiveDateTime {
+    NaiveDateTime::parse_from_str("2026-03-02 12:00", "%Y-%m-%d %H:%M").unwrap()
+  }
+
+  fn engine(hunger: f64, affection: f64, energy: f64, fun: f64) -> MoodEngine {
+    MoodEngine::new(Needs {
+      hunger,
+      affection,
+      energy,
+      fun,
+    })
+  }
+
+  #[test]
+  fn hunger_wins_over_every_other_need() {
+    assert_eq!(engine(10.0, 0.0, 0.0, 0.0).mood(), Mood::Starving);
+    assert_eq!(engine(30.0, 0.0, 0.0, 0.0).mood(), Mood::Hungry);
+    assert_eq!(engine(50.0, 0.0, 0.0, 0.0).mood(), Mood::Peckish);
+  }
+
+  #[test]
+  fn other_needs_show_in_priority_order_once_fed() {
+    assert_eq!(engine(51.0, 0.0, 20.0, 0.0).mood(), Mood::Tired);
+    assert_eq!(engine(51.0, 25.0, 21.0, 0.0).mood(), Mood::Lonely);
+    assert_eq!(engine(51.0, 26.0, 21.0, 25.0).mood(), Mood::Bored);
+    assert_eq!(engine(51.0, 26.0, 21.0, 26.0).mood(), Mood::Content);
+    assert_eq!(engine(70.0, 70.0, 70.0, 70.0).mood(), Mood::Happy);
+    assert_eq!(engine(100.0, 100.0, 100.0, 69.9).mood(), Mood::Content);
+  }
+
+  #[test]
+  fn needs_stay_within_zero_and_one_hundred() {
+    let settings = MoodSettings::default();
+    let mut mood = engine(95.0, 1.0, 50.0, 50.0);
+    mood.apply(&settings, MoodTrigger::Fed { nutrition: 40 });
+    mood.apply(&settings, MoodTrigger::Drag);
+    assert_eq!(mood.needs.hunger, 100.0);
+    assert_eq!(mood.needs.affection, 0.0);
+
+    mood.tick(&settings, noon(), 30 * 60_000, false, None);
+    assert_eq!(mood.needs.hunger, 0.0);
+    assert_eq!(mood.needs.fun, 0.0);
+    assert_eq!(mood.needs.energy, 65.0);
+    assert_eq!(mood.pumpkin_index(), 0);
+  }
+
+  #[test]
+  fn triggers_move_the_matching_needs() {
+    let settings = MoodSettings::default();
+    let mut mood = engine(20.0, 50.0, 50.0, 50.0);
+
+    mood.apply(&settings, MoodTrigger::Click);
+    assert_eq!((mood.needs.affection, mood.needs.fun), (53.0, 52.0));
+
+    mood.apply(&settings, MoodTrigger::Wander { distance_px: 500.0 });
+    assert_eq!((mood.needs.energy, mood.needs.fun), (48.0, 54.5));
+
+    mood.apply(&settings, MoodTrigger::Play);
+    assert_eq!((mood.needs.energy, mood.needs.fun), (42.0, 74.5));
+
+    mood.apply(&settings, MoodTrigger::FullRefill);
+    assert_eq!((mood.needs.hunger, mood.needs.affection), (100.0, 55.0));
+  }
+
+  #[test]
+  fn each_need_follows_its_own_curve() {
+    let settings = MoodSettings {
+      drift: NeedDrift {
+        affection: DecayCurve::Linear { per_minute: 1.0 },
+        energy: DecayCurve::Linear { per_minute: 0.0 },
+        fun: DecayCurve::Exponential {
+          half_life_minutes: 10.0,
+        },
+        energy_recovery_per_minute: 2.0,
+      },
+      ..MoodSettings::default()
+    };
+    let mut mood = engine(100.0, 80.0, 50.0, 80.0);
+    mood.tick(&settings, noon(), 10 * 60_000, false, None);
+
+    assert_eq!(mood.needs.hunger, 0.0);
+    assert_eq!(mood.needs.affection, 70.0);
+    assert_eq!(mood.needs.energy, 70.0);
+    assert_eq!(mood.needs.fun, 40.0);
+  }
+
+  #[test]
+  fn difficulty_scales_only_the_drain() {
+    let mut settings = MoodSettings::default();
+    settings.decay.difficulty = Difficulty::Relaxed;
+    let mut mood = engine(100.0, 80.0, 50.0, 80.0);
+    mood.tick(&settings, noon(), 4 * 60_000, false, None);
+
+    assert_eq!(mood.needs.hunger, 80.0);
+    assert_eq!(mood.needs.affection, 79.0);
+    assert_eq!(mood.needs.energy, 52.0);
+    assert_eq!(mood.needs.fun, 76.0);
+  }
+
+  #[test]
+  fn nothing_drifts_while_away_if_paused() {
+    let mut settings = MoodSettings::default();
+    let mut mood = engine(100.0, 80.0, 50.0, 80.0);
+    mood.tick(&settings, noon(), 60 * 60_000, true, None);
+    assert_eq!(mood.needs.hunger, 100.0);
+    assert_eq!(mood.needs.energy, 50.0);
+
+    settings.decay.pause_when_away = false;
+    mood.tick(&settings, noon(), 60_000, true, None);
+    assert_eq!(mood.needs.hunger, 90.0);
+  }
+
+  #[test]
+  fn sleeping_slows_the_drain_and_restores_energy() {
+    let settings = MoodSettings::default();
+    let sleep = SleepSettings::default();
+    let mut mood = engine(100.0, 80.0, 50.0, 80.0);
+    mood.tick(&settings, noon(), 10 * 60_000, false, Some(&sleep));
+
+    assert_eq!(mood.needs.hunger, 70.0);
+    assert_eq!(mood.needs.affection, 78.5);
+    assert_eq!(mood.needs.energy, 95.0);
+    assert_eq!(mood.needs.fun, 74.0);
+  }
+
+  #[test]
+  fn missing_profiles_fall_back_to_the_built_in_ones() {
+    let settings = MoodSettings {
+      profiles: Vec::new(),
+      ..MoodSettings::default()
+    };
+    assert_eq!(settings.profile(Mood::Bored).expression, "back");
+  }
+}
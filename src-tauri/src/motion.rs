@@ -0,0 +1,190 @@
+/// Who is currently allowed to move the character window.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MotionOwner {
+  Cursor,
+  Chase,
+  Carry,
+  Throw,
+  Game,
+  Follow,
+}
+
+impl MotionOwner {
+  /// Higher priorities take the lease away from lower ones. A pumpkin dragged
+  /// by the user always wins over anything the character decided on its own,
+  /// and a thrown character flies over whatever walk it was on. Trailing the
+  /// cursor gives way to everything.
+  fn priority(self) -> u8 {
+    match self {
+      MotionOwner::Cursor => 1,
+      MotionOwner::Chase => 2,
+      MotionOwner::Carry => 3,
+      MotionOwner::Throw => 4,
+      MotionOwner::Game => 5,
+      MotionOwner::Follow => 6,
+    }
+  }
+}
+
+/// A window that moves in lockstep with the character, `offset` away from its
+/// top-left corner.
+#[derive(Clone, Debug)]
+pub struct Attachment {
+  pub label: String,
+  pub offset_x: i32,
+  pub offset_y: i32,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Lease {
+  id: u64,
+  owner: MotionOwner,
+}
+
+/// Hands out exclusive leases on character movement so the chase, carry,
+/// mini-game, follow and cursor workers never fight over the window position.
+#[derive(Default)]
+pub struct MotionScheduler {
+  lease: Option<Lease>,
+  next_lease_id: u64,
+  attachments: Vec<Attachment>,
+}
+
+impl MotionScheduler {
+  /// Grants a lease when nobody holds one or the holder has a lower priority.
+  /// Attachments belong to the previous lease and are dropped with it.
+  pub fn acquire(&mut self, owner: MotionOwner) -> Option<u64> {
+    if let Some(current) = self.lease {
+      if current.owner.priority() >= owner.priority() {
+        return None;
+      }
+    }
+
+    self.next_lease_id = self.next_lease_id.wrapping_add(1);
+    self.lease = Some(Lease {
+      id: self.next_lease_id,
+      owner,
+    });
+    self.attachments.clear();
+    Some(self.next_lease_id)
+  }
+
+  pub fn release(&mut self, lease_id: u64) {
+    if self.holds(lease_id) {
+      self.lease = None;
+      self.attachments.clear();
+    }
+  }
+
+  pub fn is_free(&self) -> bool {
+    self.lease.is_none()
+  }
+
+  pub fn holds(&self, lease_id: u64) -> bool {
+    self.lease.is_some_and(|lease| lease.id == lease_id)
+  }
+
+  pub fn attach(&mut self, lease_id: u64, attachment: Attachment) -> bool {
+    if !self.holds(lease_id) {
+      return false;
+    }
+    self.attachments.retain(|current| current.label != attachment.label);
+    self.attachments.push(attachment);
+    true
+  }
+
+  /// Positions of the attached windows when the character sits at `(x, y)`.
+  pub fn attachment_positions(&self, x: i32, y: i32) -> Vec<(String, i32, i32)> {
+    self
+      .attachments
+      .iter()
+      .map(|attachment| {
+        (
+          attachment.label.clone(),
+          x + attachment.offset_x,
+          y + attachment.offset_y,
+        )
+      })
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn attachment(label: &str) -> Attachment {
+    Attachment {
+      label: label.into(),
+      offset_x: 10,
+      offset_y: -5,
+    }
+  }
+
+  #[test]
+  fn lower_or_equal_priority_cannot_take_the_lease() {
+    let mut scheduler = MotionScheduler::default();
+    let carry = scheduler.acquire(MotionOwner::Carry).unwrap();
+    assert!(scheduler.acquire(MotionOwner::Chase).is_none());
+    assert!(scheduler.acquire(MotionOwner::Carry).is_none());
+    assert!(scheduler.holds(carry));
+  }
+
+  #[test]
+  fn the_mini_game_outranks_carrying_but_not_a_user_drag() {
+    let mut scheduler = MotionScheduler::default();
+    let carry = scheduler.acquire(MotionOwner::Carry).unwrap();
+    let game = scheduler.acquire(MotionOwner::Game).unwrap();
+    assert!(!scheduler.holds(carry));
+    assert!(scheduler.acquire(MotionOwner::Carry).is_none());
+    assert!(scheduler.acquire(MotionOwner::Follow).is_some());
+    assert!(!scheduler.holds(game));
+  }
+
+  #[test]
+  fn higher_priority_takes_over_and_drops_attachments() {
+    let mut scheduler = MotionScheduler::default();
+    let carry = scheduler.acquire(MotionOwner::Carry).unwrap();
+    assert!(scheduler.attach(carry, attachment("pumpkin")));
+    assert_eq!(
+      scheduler.attachment_positions(100, 100),
+      vec![("pumpkin".into(), 110, 95)]
+    );
+
+    let follow = scheduler.acquire(MotionOwner::Follow).unwrap();
+    assert_ne!(carry, follow);
+    assert!(!scheduler.holds(carry));
+    assert!(scheduler.attachment_positions(100, 100).is_empty());
+    assert!(!scheduler.attach(carry, attachment("pumpkin")));
+  }
+
+  #[test]
+  fn only_the_holder_can_release() {
+    let mut scheduler = MotionScheduler::default();
+    let chase = scheduler.acquire(MotionOwner::Chase).unwrap();
+    let follow = scheduler.acquire(MotionOwner::Follow).unwrap();
+
+    scheduler.release(chase);
+    assert!(scheduler.holds(follow));
+
+    scheduler.release(follow);
+    assert!(!scheduler.holds(follow));
+    assert!(scheduler.acquire(MotionOwner::Chase).is_some());
+  }
+
+  #[test]
+  fn reattaching_a_label_replaces_it() {
+    let mut scheduler = MotionScheduler::default();
+    let lease = scheduler.acquire(MotionOwner::Carry).unwrap();
+    scheduler.attach(lease, attachment("pumpkin"));
+    scheduler.attach(
+      lease,
+      Attachment {
+        label: "pumpkin".into(),
+        offset_x: 0,
+        offset_y: 0,
+      },
+    );
+    assert_eq!(scheduler.attachment_positions(5, 6), vec![("pumpkin".into(), 5, 6)]);
+  }
+}
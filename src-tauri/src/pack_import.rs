@@ -0,0 +1,477 @@
+use crate::{
+  character::{self, CharacterManifest, BUILT_IN_PACK_ID, MANIFEST_FILE_NAME, MAX_FILE_BYTES, PACKS_DIR_NAME},
+  wav,
+};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::{
+  collections::BTreeMap,
+  fmt, fs,
+  io::{Cursor, Read},
+  path::{Path, PathBuf},
+};
+
+/// Largest total size of everything a pack installs.
+const MAX_PACK_BYTES: u64 = 96 * 1024 * 1024;
+const MAX_IMAGE_SIDE: u32 = 4096;
+const MAX_VOICE_SECS: f64 = 30.0;
+
+#[derive(Clone, Debug, Serialize)]
+pub struct CheckedFile {
+  /// `images/<name>` or `voice/<name>`.
+  pub path: String,
+  pub bytes: u64,
+  pub sha256: String,
+  /// `320x320` for images, `1.42s 44100 Hz` for voices.
+  pub detail: String,
+}
+
+/// What an import found. The pack is only installed when `problems` is empty.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct ImportReport {
+  pub pack_id: Option<String>,
+  pub installed_to: Option<PathBuf>,
+  pub files: Vec<CheckedFile>,
+  pub problems: Vec<String>,
+}
+
+impl fmt::Display for ImportReport {
+  fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for file in &self.files {
+      writeln!(
+        formatter,
+        "  {}  {} bytes  {}  sha256 {}",
+        file.path, file.bytes, file.detail, file.sha256
+      )?;
+    }
+    for problem in &self.problems {
+      writeln!(formatter, "  problem: {problem}")?;
+    }
+    match (&self.pack_id, &self.installed_to) {
+      (Some(id), Some(path)) => write!(formatter, "installed `{id}` to {}", path.display()),
+      (Some(id), None) => write!(formatter, "`{id}` was not installed"),
+      (None, _) => write!(formatter, "the pack was not installed"),
+    }
+  }
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+  Sha256::digest(bytes).iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn read_entry(reader: impl Read, name: &str) -> Result<Vec<u8>, String> {
+  character::read_limited(reader, MAX_FILE_BYTES).map_err(|error| format!("`{name}`: {error}"))
+}
+
+/// An entry name is safe when it stays inside the pack: relative, no `..`,
+/// no drive letters and no backslashes.
+fn is_safe_entry_name(name: &str) -> bool {
+  !name.is_empty()
+    && !name.starts_with('/')
+    && !name.contains('\\')
+    && !name.contains(':')
+    && name.split('/').all(|part| part != ".." && part != ".")
+}
+
+/// Reads every file in a zip pack. Entries that would land outside the pack
+/// are reported and the whole archive is refused.
+fn read_zip(path: &Path, problems: &mut Vec<String>) -> BTreeMap<String, Vec<u8>> {
+  let mut files = BTreeMap::new();
+  let opened = fs::File::open(path)
+    .map_err(|error| error.to_string())
+    .and_then(|file| zip::ZipArchive::new(file).map_err(|error| error.to_string()));
+  let mut archive = match opened {
+    Ok(archive) => archive,
+    Err(error) => {
+      problems.push(format!("cannot open {}: {}", path.display(), error));
+      return files;
+    }
+  };
+
+  let mut total = 0;
+  for index in 0..archive.len() {
+    let entry = match archive.by_index(index) {
+      Ok(entry) => entry,
+      Err(error) => {
+        problems.push(format!("zip entry {index}: {error}"));
+        continue;
+      }
+    };
+    let name = entry.name().to_string();
+    if !is_safe_entry_name(&name) || entry.enclosed_name().is_none() {
+      problems.push(format!("zip entry `{name}` points outside the pack"));
+      continue;
+    }
+    if entry.is_dir() {
+      continue;
+    }
+    total += entry.size();
+    if total > MAX_PACK_BYTES {
+      problems.push(format!("pack is larger than {} MiB", MAX_PACK_BYTES / 1024 / 1024));
+      break;
+    }
+    match read_entry(entry, &name) {
+      Ok(bytes) => {
+        files.insert(name, bytes);
+      }
+      Err(error) => problems.push(error),
+    }
+  }
+  if !problems.is_empty() {
+    files.clear();
+  }
+  files
+}
+
+/// Reads the manifest and the files it names from a pack folder.
+fn read_dir_pack(dir: &Path, problems: &mut Vec<String>) -> BTreeMap<String, Vec<u8>> {
+  let mut files = BTreeMap::new();
+  let mut wanted = vec![MANIFEST_FILE_NAME.to_string()];
+  if let Some(manifest) = fs::read(dir.join(MANIFEST_FILE_NAME))
+    .ok()
+    .and_then(|raw| serde_json::from_slice::<CharacterManifest>(&raw).ok())
+  {
+    // Names are checked by `validate` before anything is installed; skip
+    // unsafe ones here so reading never leaves the folder.
+    wanted.extend(
+      manifest
+        .referenced_files()
+        .into_iter()
+        .filter(|file| is_safe_entry_name(file)),
+    );
+  }
+
+  for name in wanted {
+    let path = dir.join(&name);
+    let Ok(file) = fs::File::open(&path) else {
+      continue;
+    };
+    match read_entry(file, &name) {
+      Ok(bytes) => {
+        files.insert(name, bytes);
+      }
+      Err(error) => problems.push(error),
+    }
+  }
+  files
+}
+
+fn check_png(bytes: &[u8]) -> Result<String, String> {
+  let decoder = png::Decoder::new(Cursor::new(bytes));
+  let mut reader = decoder.read_info().map_err(|error| error.to_string())?;
+  let (width, height) = (reader.info().width, reader.info().height);
+  if width > MAX_IMAGE_SIDE || height > MAX_IMAGE_SIDE {
+    return Err(format!(
+      "{width}x{height} is larger than {MAX_IMAGE_SIDE}x{MAX_IMAGE_SIDE}"
+    ));
+  }
+  let mut buffer = vec![0; reader.output_buffer_size()];
+  reader.next_frame(&mut buffer).map_err(|error| error.to_string())?;
+  Ok(format!("{width}x{height}"))
+}
+
+fn check_wav(bytes: &[u8]) -> Result<String, String> {
+  let info = wav::parse_wav(bytes)?;
+  if info.duration_secs < 0.05 {
+    return Err("is shorter than 50 ms".into());
+  }
+  if info.duration_secs > MAX_VOICE_SECS {
+    return Err(format!("{:.1}s is longer than {MAX_VOICE_SECS}s", info.duration_secs));
+  }
+  Ok(format!("{:.2}s {} Hz", info.duration_secs, info.sample_rate))
+}
+
+fn check_pack(files: &BTreeMap<String, Vec<u8>>, report: &mut ImportReport) -> Option<CharacterManifest> {
+  let Some(raw) = files.get(MANIFEST_FILE_NAME) else {
+    report.problems.push(format!("missing {MANIFEST_FILE_NAME}"));
+    return None;
+  };
+  let manifest = match serde_json::from_slice::<CharacterManifest>(raw) {
+    Ok(manifest) => manifest,
+    Err(error) => {
+      report.problems.push(format!("{MANIFEST_FILE_NAME}: {error}"));
+      return None;
+    }
+  };
+  report.pack_id = Some(manifest.id.clone());
+  if let Err(error) = manifest.validate() {
+    report.problems.push(format!("{MANIFEST_FILE_NAME}: {error}"));
+    return None;
+  }
+  if manifest.id == BUILT_IN_PACK_ID {
+    report.problems.push(format!(
+      "pack id `{BUILT_IN_PACK_ID}` is reserved for the built-in character"
+    ));
+  }
+
+  for path in manifest.referenced_files() {
+    let Some(bytes) = files.get(&path) else {
+      report.problems.push(format!("missing file `{path}`"));
+      continue;
+    };
+    let checked = if path.starts_with("images/") {
+      check_png(bytes)
+    } else {
+      check_wav(bytes)
+    };
+    match checked {
+      Ok(detail) => report.files.push(CheckedFile {
+        path,
+        bytes: bytes.len() as u64,
+        sha256: sha256_hex(bytes),
+        detail,
+      }),
+      Err(error) => report.problems.push(format!("`{path}`: {error}")),
+    }
+  }
+  Some(manifest)
+}
+
+/// Writes the manifest and checked files into `<data_dir>/characters/<id>`,
+/// replacing an earlier install of the same pack only once the copy is complete.
+fn install(
+  files: &BTreeMap<String, Vec<u8>>,
+  manifest: &CharacterManifest,
+  checked: &[CheckedFile],
+  data_dir: &Path,
+) -> Result<PathBuf, String> {
+  let packs_dir = data_dir.join(PACKS_DIR_NAME);
+  let target = packs_dir.join(&manifest.id);
+  let staging = packs_dir.join(format!(".{}.importing", manifest.id));
+  let _ = fs::remove_dir_all(&staging);
+
+  let write_all = || -> std::io::Result<()> {
+    fs::create_dir_all(staging.join("images"))?;
+    fs::create_dir_all(staging.join("voice"))?;
+    fs::write(staging.join(MANIFEST_FILE_NAME), &files[MANIFEST_FILE_NAME])?;
+    for file in checked {
+      fs::write(staging.join(&file.path), &files[&file.path])?;
+    }
+    if target.exists() {
+      fs::remove_dir_all(&target)?;
+    }
+    fs::rename(&staging, &target)
+  };
+  write_all().map_err(|error| {
+    let _ = fs::remove_dir_all(&staging);
+    format!("install into {} failed: {}", target.display(), error)
+  })?;
+  Ok(target)
+}
+
+/// Checks a pack folder or `.zip` and, if nothing is wrong with it, installs
+/// it and reloads the pack list.
+pub fn import_pack(source: &Path, data_dir: &Path) -> ImportReport {
+  let mut report = ImportReport::default();
+  let files = if source.is_dir() {
+    read_dir_pack(source, &mut report.problems)
+  } else if source.is_file() {
+    read_zip(source, &mut report.problems)
+  } else {
+    report.problems.push(format!("{} does not exist", source.display()));
+    return report;
+  };
+  if !report.problems.is_empty() {
+    return report;
+  }
+
+  let Some(manifest) = check_pack(&files, &mut report) else {
+    return report;
+  };
+  if !report.problems.is_empty() {
+    return report;
+  }
+
+  match install(&files, &manifest, &report.files, data_dir) {
+    Ok(path) => {
+      log::info!("installed character pack `{}` to {}", manifest.id, path.display());
+      report.installed_to = Some(path);
+      character::load_packs(data_dir);
+    }
+    Err(error) => report.problems.push(error),
+  }
+  report
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::io::Write;
+
+  fn png_bytes(width: u32, height: u32) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    let mut encoder = png::Encoder::new(&mut bytes, width, height);
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().unwrap();
+    writer.write_image_data(&vec![0; (width * height) as usize]).unwrap();
+    writer.finish().unwrap();
+    bytes
+  }
+
+  /// 8 kHz mono 8-bit PCM of silence.
+  fn wav_bytes(secs: f64) -> Vec<u8> {
+    let data_len = (8_000.0 * secs) as u32;
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(b"RIFF");
+    bytes.extend_from_slice(&(36 + data_len).to_le_bytes());
+    bytes.extend_from_slice(b"WAVEfmt ");
+    bytes.extend_from_slice(&16u32.to_le_bytes());
+    bytes.extend_from_slice(&1u16.to_le_bytes());
+    bytes.extend_from_slice(&1u16.to_le_bytes());
+    bytes.extend_from_slice(&8_000u32.to_le_bytes());
+    bytes.extend_from_slice(&8_000u32.to_le_bytes());
+    bytes.extend_from_slice(&1u16.to_le_bytes());
+    bytes.extend_from_slice(&8u16.to_le_bytes());
+    bytes.extend_from_slice(b"data");
+    bytes.extend_from_slice(&data_len.to_le_bytes());
+    bytes.resize(bytes.len() + data_len as usize, 128);
+    bytes
+  }
+
+  fn manifest_json() -> Vec<u8> {
+    let expressions: Vec<_> = character::CORE_EXPRESSIONS
+      .iter()
+      .map(|id| serde_json::json!({ "id": id, "label": id, "sprite": "face.png" }))
+      .collect();
+    serde_json::to_vec(&serde_json::json!({
+      "id": "test_pack",
+      "name": "Test",
+      "default_size": 300,
+      "expressions": expressions,
+      "voice_sets": { "drag": ["hello.wav"] },
+    }))
+    .unwrap()
+  }
+
+  fn pack_files() -> BTreeMap<String, Vec<u8>> {
+    BTreeMap::from([
+      (MANIFEST_FILE_NAME.to_string(), manifest_json()),
+      ("images/face.png".to_string(), png_bytes(4, 4)),
+      ("voice/hello.wav".to_string(), wav_bytes(0.5)),
+    ])
+  }
+
+  fn write_zip(name: &str, entries: &[(&str, &[u8])]) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("pack-import-{}-{name}.zip", std::process::id()));
+    let mut writer = zip::ZipWriter::new(fs::File::create(&path).unwrap());
+    for (entry, bytes) in entries {
+      writer
+        .start_file(*entry, zip::write::SimpleFileOptions::default())
+        .unwrap();
+      writer.write_all(bytes).unwrap();
+    }
+    writer.finish().unwrap();
+    path
+  }
+
+  #[test]
+  fn entry_names_must_stay_inside_the_pack() {
+    assert!(is_safe_entry_name(MANIFEST_FILE_NAME));
+    assert!(is_safe_entry_name("images/face.png"));
+
+    for name in [
+      "",
+      "../character.json",
+      "images/../../escape.png",
+      "./character.json",
+      "/etc/passwd",
+      "C:/Windows/evil.png",
+      "C:evil.png",
+      "images\\face.png",
+      "..\\escape.png",
+    ] {
+      assert!(!is_safe_entry_name(name), "accepted `{name}`");
+    }
+  }
+
+  #[test]
+  fn zip_with_an_unsafe_entry_is_refused_whole() {
+    let path = write_zip(
+      "unsafe",
+      &[
+        (MANIFEST_FILE_NAME, &manifest_json()),
+        ("images/face.png", &png_bytes(4, 4)),
+        ("../escape.png", b"outside"),
+      ],
+    );
+    let mut problems = Vec::new();
+    let files = read_zip(&path, &mut problems);
+    fs::remove_file(&path).unwrap();
+
+    assert!(files.is_empty());
+    assert_eq!(
+      problems,
+      vec!["zip entry `../escape.png` points outside the pack".to_string()]
+    );
+  }
+
+  #[test]
+  fn zip_without_problems_is_read_fully() {
+    let path = write_zip(
+      "safe",
+      &[
+        (MANIFEST_FILE_NAME, &manifest_json()),
+        ("images/face.png", &png_bytes(4, 4)),
+      ],
+    );
+    let mut problems = Vec::new();
+    let files = read_zip(&path, &mut problems);
+    fs::remove_file(&path).unwrap();
+
+    assert!(problems.is_empty());
+    assert_eq!(
+      files.keys().collect::<Vec<_>>(),
+      vec![MANIFEST_FILE_NAME, "images/face.png"]
+    );
+  }
+
+  #[test]
+  fn files_over_the_size_limit_are_not_read() {
+    let oversized = std::io::repeat(0).take(MAX_FILE_BYTES + 1);
+    assert!(read_entry(oversized, "big.png").unwrap_err().contains("larger than"));
+    let at_limit = std::io::repeat(0).take(MAX_FILE_BYTES);
+    assert_eq!(read_entry(at_limit, "big.png").unwrap().len() as u64, MAX_FILE_BYTES);
+  }
+
+  #[test]
+  fn a_complete_pack_passes() {
+    let mut report = ImportReport::default();
+    let manifest = check_pack(&pack_files(), &mut report).unwrap();
+
+    assert_eq!(manifest.id, "test_pack");
+    assert!(report.problems.is_empty(), "{:?}", report.problems);
+    assert_eq!(report.files.len(), 2);
+    assert_eq!(report.files[0].detail, "4x4");
+    assert_eq!(report.files[1].detail, "0.50s 8000 Hz");
+  }
+
+  #[test]
+  fn missing_files_are_reported() {
+    let mut files = pack_files();
+    files.remove("voice/hello.wav");
+    let mut report = ImportReport::default();
+    check_pack(&files, &mut report);
+
+    assert_eq!(report.problems, vec!["missing file `voice/hello.wav`".to_string()]);
+
+    files.remove(MANIFEST_FILE_NAME);
+    let mut report = ImportReport::default();
+    assert!(check_pack(&files, &mut report).is_none());
+    assert_eq!(report.problems, vec![format!("missing {MANIFEST_FILE_NAME}")]);
+  }
+
+  #[test]
+  fn images_and_voices_over_the_limits_are_reported() {
+    let mut files = pack_files();
+    files.insert("images/face.png".into(), png_bytes(MAX_IMAGE_SIDE + 1, 1));
+    files.insert("voice/hello.wav".into(), wav_bytes(MAX_VOICE_SECS + 1.0));
+    let mut report = ImportReport::default();
+    check_pack(&files, &mut report);
+
+    assert_eq!(report.problems.len(), 2, "{:?}", report.problems);
+    assert!(report.problems[0].starts_with("`images/face.png`: 4097x1 is larger than"));
+    assert!(report.problems[1].starts_with("`voice/hello.wav`: 31.0s is longer than"));
+    assert!(report.files.is_empty());
+  }
+}
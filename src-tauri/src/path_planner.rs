@@ -0,0 +1,435 @@
+use serde::{Deserialize, Serialize};
+use std::{
+  cmp::{Ordering, Reverse},
+  collections::BinaryHeap,
+};
+
+const STRAIGHT_COST: u32 = 10;
+const DIAGONAL_COST: u32 = 14;
+const MAX_GRID_NODES: usize = 40_000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rect {
+  pub x: i32,
+  pub y: i32,
+  pub width: i32,
+  pub height: i32,
+}
+
+impl Rect {
+  pub fn new(x: i32, y: i32, width: i32, height: i32) -> Self {
+    Self {
+      x,
+      y,
+      width: width.max(0),
+      height: height.max(0),
+    }
+  }
+
+  pub fn right(&self) -> i32 {
+    self.x + self.width
+  }
+
+  pub fn bottom(&self) -> i32 {
+    self.y + self.height
+  }
+
+  /// Strict interior test, so a mover sliding along an edge is not considered blocked.
+  pub fn contains_point(&self, x: i32, y: i32) -> bool {
+    x > self.x && x < self.right() && y > self.y && y < self.bottom()
+  }
+
+  /// Grows the rect so that it covers every top-left position at which a
+  /// `mover_width` x `mover_height` window would overlap it.
+  pub fn inflate_for_mover(&self, mover_width: i32, mover_height: i32) -> Rect {
+    Rect::new(
+      self.x - mover_width,
+      self.y - mover_height,
+      self.width + mover_width,
+      self.height + mover_height,
+    )
+  }
+}
+
+/// Whether two rects overlap or share an edge.
+#[allow(clippy::too_many_arguments)]
+pub fn touching_or_overlapping(
+  ax: i32,
+  ay: i32,
+  aw: i32,
+  ah: i32,
+  bx: i32,
+  by: i32,
+  bw: i32,
+  bh: i32,
+) -> bool {
+  let a_left = ax;
+  let a_right = ax + aw;
+  let a_top = ay;
+  let a_bottom = ay + ah;
+  let b_left = bx;
+  let b_right = bx + bw;
+  let b_top = by;
+  let b_bottom = by + bh;
+
+  !(a_right < b_left || a_left > b_right || a_bottom < b_top || a_top > b_bottom)
+}
+
+struct Grid {
+  origin_x: i32,
+  origin_y: i32,
+  cell: i32,
+  cols: usize,
+  rows: usize,
+  max_x: i32,
+  max_y: i32,
+}
+
+impl Grid {
+  fn point(&self, col: usize, row: usize) -> (i32, i32) {
+    (
+      (self.origin_x + col as i32 * self.cell).min(self.max_x),
+      (self.origin_y + row as i32 * self.cell).min(self.max_y),
+    )
+  }
+
+  fn nearest(&self, x: i32, y: i32) -> (usize, usize) {
+    let col = ((x - self.origin_x) as f64 / self.cell as f64).round().max(0.0) as usize;
+    let row = ((y - self.origin_y) as f64 / self.cell as f64).round().max(0.0) as usize;
+    (col.min(self.cols - 1), row.min(self.rows - 1))
+  }
+}
+
+fn is_blocked(obstacles: &[Rect], x: i32, y: i32) -> bool {
+  obstacles.iter().any(|obstacle| obstacle.contains_point(x, y))
+}
+
+fn segment_is_clear(obstacles: &[Rect], from: (i32, i32), to: (i32, i32), sample_step: i32) -> bool {
+  let delta_x = (to.0 - from.0) as f64;
+  let delta_y = (to.1 - from.1) as f64;
+  let distance = (delta_x * delta_x + delta_y * delta_y).sqrt();
+  let samples = (distance / sample_step.max(1) as f64).ceil().max(1.0) as i32;
+
+  (0..=samples).all(|sample| {
+    let t = sample as f64 / samples as f64;
+    let x = (from.0 as f64 + delta_x * t).round() as i32;
+    let y = (from.1 as f64 + delta_y * t).round() as i32;
+    !is_blocked(obstacles, x, y)
+  })
+}
+
+fn octile_distance(from: (usize, usize), to: (usize, usize)) -> u32 {
+  let delta_x = (from.0 as i64 - to.0 as i64).unsigned_abs() as u32;
+  let delta_y = (from.1 as i64 - to.1 as i64).unsigned_abs() as u32;
+  let diagonal = delta_x.min(delta_y);
+  let straight = delta_x.max(delta_y) - diagonal;
+  diagonal * DIAGONAL_COST + straight * STRAIGHT_COST
+}
+
+#[derive(PartialEq, Eq)]
+struct OpenNode {
+  estimate: u32,
+  index: usize,
+}
+
+impl Ord for OpenNode {
+  fn cmp(&self, other: &Self) -> Ordering {
+    self
+      .estimate
+      .cmp(&other.estimate)
+      .then_with(|| self.index.cmp(&other.index))
+  }
+}
+
+impl PartialOrd for OpenNode {
+  fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+fn smooth_path(obstacles: &[Rect], points: &[(i32, i32)], sample_step: i32) -> Vec<(i32, i32)> {
+  if points.len() <= 2 {
+    return points.to_vec();
+  }
+
+  let mut smoothed = vec![points[0]];
+  let mut anchor = 0;
+  while anchor < points.len() - 1 {
+    let mut furthest = anchor + 1;
+    for candidate in (anchor + 2..points.len()).rev() {
+      if segment_is_clear(obstacles, points[anchor], points[candidate], sample_step) {
+        furthest = candidate;
+        break;
+      }
+    }
+    smoothed.push(points[furthest]);
+    anchor = furthest;
+  }
+
+  smoothed
+}
+
+/// Plans a polyline for the top-left corner of a `mover_width` x `mover_height`
+/// window from `start` to `goal`, staying inside `bounds` and outside `keep_out`.
+///
+/// Keep-out rects already covering the start or goal are ignored: the walker
+/// cannot avoid a zone it is standing in, and refusing to walk would be worse.
+/// Returns `None` when no route exists on the grid.
+pub fn plan_path(
+  start: (i32, i32),
+  goal: (i32, i32),
+  mover_width: i32,
+  mover_height: i32,
+  bounds: Rect,
+  keep_out: &[Rect],
+  cell_size: i32,
+) -> Option<Vec<(i32, i32)>> {
+  let cell = cell_size.max(4);
+  let obstacles: Vec<Rect> = keep_out
+    .iter()
+    .map(|zone| zone.inflate_for_mover(mover_width, mover_height))
+    .filter(|zone| !zone.contains_point(start.0, start.1) && !zone.contains_point(goal.0, goal.1))
+    .collect();
+
+  if segment_is_clear(&obstacles, start, goal, cell / 2) {
+    return Some(vec![start, goal]);
+  }
+
+  let origin_x = bounds.x.min(start.0).min(goal.0);
+  let origin_y = bounds.y.min(start.1).min(goal.1);
+  let max_x = (bounds.right() - mover_width).max(bounds.x).max(start.0).max(goal.0);
+  let max_y = (bounds.bottom() - mover_height).max(bounds.y).max(start.1).max(goal.1);
+  let grid = Grid {
+    origin_x,
+    origin_y,
+    cell,
+    cols: ((max_x - origin_x) / cell + 2) as usize,
+    rows: ((max_y - origin_y) / cell + 2) as usize,
+    max_x,
+    max_y,
+  };
+  if grid.cols * grid.rows > MAX_GRID_NODES {
+    return None;
+  }
+
+  let blocked: Vec<bool> = (0..grid.rows)
+    .flat_map(|row| (0..grid.cols).map(move |col| (col, row)))
+    .map(|(col, row)| {
+      let (x, y) = grid.point(col, row);
+      is_blocked(&obstacles, x, y)
+    })
+    .collect();
+
+  let start_cell = grid.nearest(start.0, start.1);
+  let goal_cell = grid.nearest(goal.0, goal.1);
+  let to_index = |cell: (usize, usize)| cell.1 * grid.cols + cell.0;
+  let start_index = to_index(start_cell);
+  let goal_index = to_index(goal_cell);
+
+  let mut best_cost = vec![u32::MAX; grid.cols * grid.rows];
+  let mut came_from = vec![usize::MAX; grid.cols * grid.rows];
+  let mut open = BinaryHeap::new();
+  best_cost[start_index] = 0;
+  open.push(Reverse(OpenNode {
+    estimate: octile_distance(start_cell, goal_cell),
+    index: start_index,
+  }));
+
+  while let Some(Reverse(OpenNode { index, .. })) = open.pop() {
+    if index == goal_index {
+      break;
+    }
+
+    let col = index % grid.cols;
+    let row = index / grid.cols;
+    for (step_x, step_y) in [(-1, 0), (1, 0), (0, -1), (0, 1), (-1, -1), (1, -1), (-1, 1), (1, 1)] {
+      let next_col = col as i64 + step_x;
+      let next_row = row as i64 + step_y;
+      if next_col < 0 || next_row < 0 || next_col >= grid.cols as i64 || next_row >= grid.rows as i64 {
+        continue;
+      }
+
+      let next_cell = (next_col as usize, next_row as usize);
+      let next_index = to_index(next_cell);
+      if blocked[next_index] && next_index != goal_index {
+        continue;
+      }
+
+      let is_diagonal = step_x != 0 && step_y != 0;
+      if is_diagonal {
+        let side_a = to_index((next_cell.0, row));
+        let side_b = to_index((col, next_cell.1));
+        if blocked[side_a] || blocked[side_b] {
+          continue;
+        }
+      }
+
+      let step_cost = if is_diagonal { DIAGONAL_COST } else { STRAIGHT_COST };
+      let next_cost = best_cost[index].saturating_add(step_cost);
+      if next_cost < best_cost[next_index] {
+        best_cost[next_index] = next_cost;
+        came_from[next_index] = index;
+        open.push(Reverse(OpenNode {
+          estimate: next_cost + octile_distance(next_cell, goal_cell),
+          index: next_index,
+        }));
+      }
+    }
+  }
+
+  if best_cost[goal_index] == u32::MAX {
+    return None;
+  }
+
+  let mut cells = vec![goal_index];
+  let mut cursor = goal_index;
+  while cursor != start_index {
+    cursor = came_from[cursor];
+    cells.push(cursor);
+  }
+  cells.reverse();
+
+  let mut points = vec![start];
+  points.extend(
+    cells
+      .iter()
+      .skip(1)
+      .take(cells.len().saturating_sub(2))
+      .map(|index| grid.point(index % grid.cols, index / grid.cols)),
+  );
+  points.push(goal);
+
+  Some(smooth_path(&obstacles, &points, cell / 2))
+}
+
+/// Total length of a polyline in pixels.
+pub fn path_length(points: &[(i32, i32)]) -> f64 {
+  points
+    .windows(2)
+    .map(|pair| {
+      let delta_x = (pair[1].0 - pair[0].0) as f64;
+      let delta_y = (pair[1].1 - pair[0].1) as f64;
+      (delta_x * delta_x + delta_y * delta_y).sqrt()
+    })
+    .sum()
+}
+
+/// Position and unit direction at arc length `distance` along the polyline.
+pub fn point_along(points: &[(i32, i32)], distance: f64) -> ((f64, f64), (f64, f64)) {
+  let mut remaining = distance.max(0.0);
+  let mut last_direction = (1.0, 0.0);
+
+  for pair in points.windows(2) {
+    let delta_x = (pair[1].0 - pair[0].0) as f64;
+    let delta_y = (pair[1].1 - pair[0].1) as f64;
+    let length = (delta_x * delta_x + delta_y * delta_y).sqrt();
+    if length < f64::EPSILON {
+      continue;
+    }
+
+    last_direction = (delta_x / length, delta_y / length);
+    if remaining <= length {
+      let t = remaining / length;
+      return (
+        (pair[0].0 as f64 + delta_x * t, pair[0].1 as f64 + delta_y * t),
+        last_direction,
+      );
+    }
+    remaining -= length;
+  }
+
+  let end = points.last().copied().unwrap_or((0, 0));
+  ((end.0 as f64, end.1 as f64), last_direction)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const MOVER: i32 = 100;
+  const CELL: i32 = 20;
+
+  fn screen() -> Rect {
+    Rect::new(0, 0, 1920, 1080)
+  }
+
+  /// Whether the window's top-left corner ever enters the inflated zone.
+  fn crosses(path: &[(i32, i32)], zone: Rect) -> bool {
+    let inflated = zone.inflate_for_mover(MOVER, MOVER);
+    let length = path_length(path);
+    let mut distance = 0.0;
+    while distance <= length {
+      let ((x, y), _) = point_along(path, distance);
+      if inflated.contains_point(x.round() as i32, y.round() as i32) {
+        return true;
+      }
+      distance += 2.0;
+    }
+    false
+  }
+
+  #[test]
+  fn goes_straight_without_zones() {
+    let path = plan_path((0, 400), (800, 400), MOVER, MOVER, screen(), &[], CELL);
+    assert_eq!(path, Some(vec![(0, 400), (800, 400)]));
+  }
+
+  #[test]
+  fn routes_around_a_keep_out_zone() {
+    let zone = Rect::new(350, 300, 100, 300);
+    let path = plan_path((0, 400), (800, 400), MOVER, MOVER, screen(), &[zone], CELL).unwrap();
+
+    assert!(path.len() > 2);
+    assert_eq!(path.first(), Some(&(0, 400)));
+    assert_eq!(path.last(), Some(&(800, 400)));
+    assert!(!crosses(&path, zone));
+    assert!(crosses(&[(0, 400), (800, 400)], zone));
+  }
+
+  #[test]
+  fn zones_around_the_start_or_goal_are_ignored() {
+    let around_start = Rect::new(-50, 350, 200, 200);
+    let path = plan_path((0, 400), (800, 400), MOVER, MOVER, screen(), &[around_start], CELL);
+    assert_eq!(path, Some(vec![(0, 400), (800, 400)]));
+
+    let around_goal = Rect::new(750, 350, 200, 200);
+    let path = plan_path((0, 400), (800, 400), MOVER, MOVER, screen(), &[around_goal], CELL);
+    assert_eq!(path, Some(vec![(0, 400), (800, 400)]));
+  }
+
+  #[test]
+  fn a_wall_across_the_screen_is_unreachable() {
+    let wall = Rect::new(900, -200, 50, 1500);
+    assert_eq!(
+      plan_path((0, 400), (1500, 400), MOVER, MOVER, screen(), &[wall], CELL),
+      None
+    );
+  }
+
+  #[test]
+  fn touching_counts_shared_edges_and_corners() {
+    assert!(touching_or_overlapping(0, 0, 100, 100, 50, 50, 100, 100));
+    assert!(touching_or_overlapping(0, 0, 100, 100, 100, 0, 50, 50));
+    assert!(touching_or_overlapping(0, 0, 100, 100, 100, 100, 50, 50));
+    assert!(touching_or_overlapping(0, 0, 100, 100, 20, 20, 10, 10));
+    assert!(!touching_or_overlapping(0, 0, 100, 100, 101, 0, 50, 50));
+    assert!(!touching_or_overlapping(0, 0, 100, 100, 0, -51, 50, 50));
+  }
+
+  #[test]
+  fn point_along_follows_each_segment() {
+    let path = [(0, 0), (100, 0), (100, 100)];
+    assert_eq!(path_length(&path), 200.0);
+    assert_eq!(point_along(&path, -5.0), ((0.0, 0.0), (1.0, 0.0)));
+    assert_eq!(point_along(&path, 50.0), ((50.0, 0.0), (1.0, 0.0)));
+    assert_eq!(point_along(&path, 150.0), ((100.0, 50.0), (0.0, 1.0)));
+    assert_eq!(point_along(&path, 500.0), ((100.0, 100.0), (0.0, 1.0)));
+  }
+
+  #[test]
+  fn point_along_skips_zero_length_segments() {
+    let path = [(0, 0), (0, 0), (10, 0)];
+    assert_eq!(point_along(&path, 5.0), ((5.0, 0.0), (1.0, 0.0)));
+    assert_eq!(point_along(&[], 5.0), ((0.0, 0.0), (1.0, 0.0)));
+  }
+}
@@ -0,0 +1,139 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PumpkinStage {
+  Fresh,
+  Ripe,
+  Rotting,
+  Gone,
+}
+
+/// Stage durations and the share of a food's nutrition it still gives in each stage.
+///
+/// The chase gives up when the pumpkin leaves the fresh stage, so
+/// `fresh_secs` doubles as the chase timeout.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PumpkinLifeSettings {
+  pub fresh_secs: u64,
+  pub ripe_secs: u64,
+  pub rotting_secs: u64,
+  pub fresh_nutrition_percent: u32,
+  pub ripe_nutrition_percent: u32,
+  pub rotting_nutrition_percent: u32,
+}
+
+impl Default for PumpkinLifeSettings {
+  fn default() -> Self {
+    Self {
+      fresh_secs: 5,
+      ripe_secs: 25,
+      rotting_secs: 30,
+      fresh_nutrition_percent: 100,
+      ripe_nutrition_percent: 100,
+      rotting_nutrition_percent: 40,
+    }
+  }
+}
+
+impl PumpkinLifeSettings {
+  fn stage_ends_ms(&self) -> [(PumpkinStage, u64); 3] {
+    let fresh_end = self.fresh_secs.saturating_mul(1000);
+    let ripe_end = fresh_end.saturating_add(self.ripe_secs.saturating_mul(1000));
+    let rotting_end = ripe_end.saturating_add(self.rotting_secs.saturating_mul(1000));
+    [
+      (PumpkinStage::Fresh, fresh_end),
+      (PumpkinStage::Ripe, ripe_end),
+      (PumpkinStage::Rotting, rotting_end),
+    ]
+  }
+
+  pub fn stage_at(&self, elapsed_ms: u64) -> PumpkinStage {
+    self
+      .stage_ends_ms()
+      .iter()
+      .find(|(_, end_ms)| elapsed_ms < *end_ms)
+      .map(|(stage, _)| *stage)
+      .unwrap_or(PumpkinStage::Gone)
+  }
+
+  /// Milliseconds from spawn at which the stage after `elapsed_ms` begins.
+  pub fn next_change_ms(&self, elapsed_ms: u64) -> Option<u64> {
+    self
+      .stage_ends_ms()
+      .iter()
+      .map(|(_, end_ms)| *end_ms)
+      .find(|end_ms| elapsed_ms < *end_ms)
+  }
+
+  pub fn nutrition_percent(&self, stage: PumpkinStage) -> u32 {
+    match stage {
+      PumpkinStage::Fresh => self.fresh_nutrition_percent,
+      PumpkinStage::Ripe => self.ripe_nutrition_percent,
+      PumpkinStage::Rotting => self.rotting_nutrition_percent,
+      PumpkinStage::Gone => 0,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const DT_MS: u64 = 500;
+
+  #[test]
+  fn stages_advance_at_their_boundaries() {
+    let settings = PumpkinLifeSettings::default();
+    let mut changes = Vec::new();
+    let mut stage = settings.stage_at(0);
+    assert_eq!(stage, PumpkinStage::Fresh);
+
+    let mut elapsed_ms = 0;
+    while stage != PumpkinStage::Gone {
+      elapsed_ms += DT_MS;
+      let next = settings.stage_at(elapsed_ms);
+      if next != stage {
+        changes.push((elapsed_ms, next));
+        stage = next;
+      }
+    }
+
+    assert_eq!(
+      changes,
+      vec![
+        (5_000, PumpkinStage::Ripe),
+        (30_000, PumpkinStage::Rotting),
+        (60_000, PumpkinStage::Gone),
+      ]
+    );
+  }
+
+  #[test]
+  fn next_change_points_at_the_following_stage() {
+    let settings = PumpkinLifeSettings::default();
+    assert_eq!(settings.next_change_ms(0), Some(5_000));
+    assert_eq!(settings.next_change_ms(5_000), Some(30_000));
+    assert_eq!(settings.next_change_ms(59_500), Some(60_000));
+    assert_eq!(settings.next_change_ms(60_000), None);
+  }
+
+  #[test]
+  fn rotting_food_is_worth_less() {
+    let settings = PumpkinLifeSettings::default();
+    assert_eq!(settings.nutrition_percent(settings.stage_at(1_000)), 100);
+    assert_eq!(settings.nutrition_percent(settings.stage_at(40_000)), 40);
+    assert_eq!(settings.nutrition_percent(settings.stage_at(60_000)), 0);
+  }
+
+  #[test]
+  fn zero_length_stages_are_skipped() {
+    let settings = PumpkinLifeSettings {
+      fresh_secs: 0,
+      ripe_secs: 0,
+      ..PumpkinLifeSettings::default()
+    };
+    assert_eq!(settings.stage_at(0), PumpkinStage::Rotting);
+  }
+}
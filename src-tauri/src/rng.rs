@@ -0,0 +1,43 @@
+/// Small xorshift generator so a whole plan can be drawn from one seed.
+pub struct Dice(u64);
+
+impl Dice {
+  pub fn new(seed: u64) -> Self {
+    Self(seed | 1)
+  }
+
+  pub fn next_u64(&mut self) -> u64 {
+    self.0 ^= self.0 << 13;
+    self.0 ^= self.0 >> 7;
+    self.0 ^= self.0 << 17;
+    self.0
+  }
+
+  /// Uniform in `[0, 1)`.
+  pub fn next_f64(&mut self) -> f64 {
+    (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn same_seed_same_rolls() {
+    let mut first = Dice::new(42);
+    let mut second = Dice::new(42);
+    for _ in 0..100 {
+      assert_eq!(first.next_u64(), second.next_u64());
+    }
+  }
+
+  #[test]
+  fn zero_seed_still_rolls_and_floats_stay_in_range() {
+    let mut dice = Dice::new(0);
+    assert_ne!(dice.next_u64(), 0);
+    for _ in 0..1_000 {
+      assert!((0.0..1.0).contains(&dice.next_f64()));
+    }
+  }
+}
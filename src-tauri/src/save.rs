@@ -0,0 +1,183 @@
+use crate::{
+  bond::BondRecord, feeder::FeederRecord, flee::FleeRecord, hide_seek::HideSeekRecord, journal::JournalEntry,
+  mood::Needs, sickness::SicknessState,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::{
+  fs,
+  path::{Path, PathBuf},
+  sync::{Mutex, OnceLock},
+};
+
+const SAVE_FILE_NAME: &str = "save.json";
+const SAVE_VERSION: u32 = 2;
+/// Bond points credited per mini-game win when an old save gains a bond.
+const SEED_BOND_POINTS_PER_WIN: f64 = 1.0;
+
+static SAVE: OnceLock<Mutex<SaveData>> = OnceLock::new();
+static SAVE_PATH: OnceLock<PathBuf> = OnceLock::new();
+
+/// Game progress that outlives a session. Unlike `settings.json` this is
+/// written by the app itself and never meant to be edited by hand.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SaveData {
+  pub version: u32,
+  /// Food ids in the order they were stashed.
+  pub stash: Vec<String>,
+  pub flee: FleeRecord,
+  pub hide_seek: HideSeekRecord,
+  /// `None` until the first save after needs moved out of the frontend.
+  pub needs: Option<Needs>,
+  pub sickness: SicknessState,
+  /// Newest last.
+  pub journal: Vec<JournalEntry>,
+  pub bond: BondRecord,
+  pub feeder: FeederRecord,
+}
+
+impl Default for SaveData {
+  fn default() -> Self {
+    Self {
+      version: SAVE_VERSION,
+      stash: Vec::new(),
+      flee: FleeRecord::default(),
+      hide_seek: HideSeekRecord::default(),
+      needs: None,
+      sickness: SicknessState::default(),
+      journal: Vec::new(),
+      bond: BondRecord::default(),
+      feeder: FeederRecord::default(),
+    }
+  }
+}
+
+/// Brings an older save up to `SAVE_VERSION`. Files without a version predate
+/// versioning and are treated as version 1.
+fn migrate(mut raw: Value) -> Result<SaveData, String> {
+  let version = raw.get("version").and_then(Value::as_u64).unwrap_or(1) as u32;
+  if version > SAVE_VERSION {
+    return Err(format!(
+      "save version {} is newer than supported {}",
+      version, SAVE_VERSION
+    ));
+  }
+
+  if version < 2 {
+    migrate_v1_to_v2(&mut raw);
+  }
+
+  if let Some(object) = raw.as_object_mut() {
+    object.insert("version".into(), SAVE_VERSION.into());
+  }
+  serde_json::from_value(raw).map_err(|error| error.to_string())
+}
+
+/// Version 2 added the bond. Players upgrading from version 1 get credit for
+/// the games they already won instead of starting as strangers.
+fn migrate_v1_to_v2(raw: &mut Value) {
+  let count = |pointer: &str| raw.pointer(pointer).and_then(Value::as_u64).unwrap_or(0);
+  let wins = count("/flee/wins") + count("/hide_seek/found");
+  if let Some(object) = raw.as_object_mut() {
+    object
+      .entry("bond")
+      .or_insert_with(|| json!({ "points": wins as f64 * SEED_BOND_POINTS_PER_WIN }));
+  }
+}
+
+fn save_state() -> &'static Mutex<SaveData> {
+  SAVE.get_or_init(|| Mutex::new(SaveData::default()))
+}
+
+/// Reads `save.json` from `data_dir`. A save that cannot be read is kept on
+/// disk untouched and the session starts fresh without overwriting it.
+pub fn load_save(data_dir: &Path) {
+  let path = data_dir.join(SAVE_FILE_NAME);
+  let Ok(raw) = fs::read_to_string(&path) else {
+    let _ = SAVE_PATH.set(path);
+    return;
+  };
+
+  let parsed = serde_json::from_str::<Value>(&raw)
+    .map_err(|error| error.to_string())
+    .and_then(migrate);
+  match parsed {
+    Ok(save) => {
+      let _ = SAVE_PATH.set(path);
+      if let Ok(mut current) = save_state().lock() {
+        *current = save;
+      }
+    }
+    Err(error) => log::warn!("not using {}: {}", path.display(), error),
+  }
+}
+
+pub fn current_save() -> SaveData {
+  save_state().lock().map(|save| save.clone()).unwrap_or_default()
+}
+
+/// Applies `change` to the save and writes it out. The lock is held until the
+/// file is on disk so two updates cannot land out of order, and the file is
+/// replaced by renaming a finished temporary copy over it, so a crash mid-write
+/// leaves the previous save intact.
+pub fn update_save<T>(change: impl FnOnce(&mut SaveData) -> T) -> Result<T, String> {
+  let mut save = save_state().lock().map_err(|error| error.to_string())?;
+  let result = change(&mut save);
+
+  let Some(path) = SAVE_PATH.get() else {
+    return Ok(result);
+  };
+  write_atomically(path, &save)?;
+  Ok(result)
+}
+
+fn write_atomically(path: &Path, save: &SaveData) -> Result<(), String> {
+  if let Some(parent) = path.parent() {
+    fs::create_dir_all(parent).map_err(|error| error.to_string())?;
+  }
+  let raw = serde_json::to_string_pretty(save).map_err(|error| error.to_string())?;
+  let temporary = path.with_extension("json.tmp");
+  fs::write(&temporary, raw).map_err(|error| error.to_string())?;
+  fs::rename(&temporary, path).map_err(|error| error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn unversioned_saves_migrate_and_newer_ones_are_refused() {
+    let save = migrate(json!({ "stash": ["pumpkin"] })).unwrap();
+    assert_eq!(save.version, SAVE_VERSION);
+    assert_eq!(save.stash, vec!["pumpkin".to_string()]);
+
+    assert!(migrate(json!({ "version": SAVE_VERSION + 1 })).is_err());
+  }
+
+  #[test]
+  fn version_one_saves_seed_the_bond_from_past_wins() {
+    let save = migrate(json!({ "version": 1, "flee": { "wins": 3 }, "hide_seek": { "found": 2 } })).unwrap();
+    assert_eq!(save.bond.points, 5.0 * SEED_BOND_POINTS_PER_WIN);
+
+    let kept = migrate(json!({ "version": 1, "flee": { "wins": 3 }, "bond": { "points": 12.0 } })).unwrap();
+    assert_eq!(kept.bond.points, 12.0);
+  }
+
+  #[test]
+  fn writes_replace_the_file_without_leaving_a_temporary_copy() {
+    let dir = std::env::temp_dir().join(format!("save-test-{}", std::process::id()));
+    let path = dir.join(SAVE_FILE_NAME);
+    let mut save = SaveData::default();
+
+    write_atomically(&path, &save).unwrap();
+    save.stash.push("apple".into());
+    write_atomically(&path, &save).unwrap();
+
+    let raw = fs::read_to_string(&path).unwrap();
+    let reloaded = migrate(serde_json::from_str(&raw).unwrap()).unwrap();
+    assert_eq!(reloaded.stash, vec!["apple".to_string()]);
+    assert!(!path.with_extension("json.tmp").exists());
+    let _ = fs::remove_dir_all(dir);
+  }
+}
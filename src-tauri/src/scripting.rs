@@ -0,0 +1,392 @@
+//! User scripts in Rhai. Every `*.rhai` file in the scripts folder may define
+//! `on_eaten(food, nutrition)`, `on_timeout()`, `on_clicked()` and
+//! `on_hour_changed(hour)`. Scripts cannot touch files or the network; all
+//! they can do is queue commands through the functions registered below.
+
+use rhai::{module_resolvers::DummyModuleResolver, CallFnOptions, Dynamic, Engine, Scope, AST};
+use serde::{Deserialize, Serialize};
+use std::{
+  collections::BTreeMap,
+  fs,
+  path::{Path, PathBuf},
+  sync::{Arc, Mutex},
+  time::{Duration, Instant, SystemTime},
+};
+
+pub const SCRIPTS_DIR_NAME: &str = "scripts";
+
+/// Longest text `say` shows.
+const SAY_LIMIT: usize = 200;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScriptingSettings {
+  pub enabled: bool,
+  /// Wall-clock budget for one handler call.
+  pub time_limit_ms: u64,
+  pub max_operations: u64,
+  /// How often the scripts folder is checked for changes.
+  pub reload_interval_ms: u64,
+}
+
+impl Default for ScriptingSettings {
+  fn default() -> Self {
+    Self {
+      enabled: true,
+      time_limit_ms: 50,
+      max_operations: 200_000,
+      reload_interval_ms: 2000,
+    }
+  }
+}
+
+#[derive(Clone, Debug)]
+pub enum ScriptEvent {
+  Eaten { food: String, nutrition: u32 },
+  Timeout,
+  Clicked,
+  HourChanged { hour: u32 },
+}
+
+impl ScriptEvent {
+  fn handler(&self) -> &'static str {
+    match self {
+      ScriptEvent::Eaten { .. } => "on_eaten",
+      ScriptEvent::Timeout => "on_timeout",
+      ScriptEvent::Clicked => "on_clicked",
+      ScriptEvent::HourChanged { .. } => "on_hour_changed",
+    }
+  }
+
+  fn args(&self) -> Vec<Dynamic> {
+    match self {
+      ScriptEvent::Eaten { food, nutrition } => vec![food.clone().into(), (*nutrition as i64).into()],
+      ScriptEvent::HourChanged { hour } => vec![(*hour as i64).into()],
+      ScriptEvent::Timeout | ScriptEvent::Clicked => Vec::new(),
+    }
+  }
+}
+
+/// What a script asked for. Everything but `SpawnPumpkin` is played by the frontend.
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum ScriptCommand {
+  MoveBy { dx: i32 },
+  Say { text: String },
+  SetExpression { expression: String },
+  PlayVoice { voice: String },
+  SpawnPumpkin { food: Option<String> },
+}
+
+fn is_plain_name(name: &str) -> bool {
+  !name.is_empty() && !name.contains("..") && !name.contains('/') && !name.contains('\\')
+}
+
+struct LoadedScript {
+  modified: Option<SystemTime>,
+  /// `None` while the file does not compile.
+  ast: Option<AST>,
+}
+
+pub struct ScriptHost {
+  engine: Engine,
+  scripts: BTreeMap<PathBuf, LoadedScript>,
+  deadline: Arc<Mutex<Instant>>,
+  output: Arc<Mutex<Vec<ScriptCommand>>>,
+}
+
+impl Default for ScriptHost {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl ScriptHost {
+  pub fn new() -> Self {
+    let deadline = Arc::new(Mutex::new(Instant::now()));
+    let output: Arc<Mutex<Vec<ScriptCommand>>> = Arc::new(Mutex::new(Vec::new()));
+
+    let mut engine = Engine::new();
+    engine.set_module_resolver(DummyModuleResolver::new());
+    engine.disable_symbol("eval");
+    engine.set_max_call_levels(32);
+    engine.set_max_expr_depths(64, 32);
+    engine.set_max_string_size(10_000);
+    engine.set_max_array_size(1_000);
+    engine.set_max_map_size(1_000);
+    engine.on_print(|text| log::info!("script: {}", text));
+    engine.on_debug(|text, source, position| {
+      log::info!("script {}:{}: {}", source.unwrap_or("?"), position, text);
+    });
+
+    let progress_deadline = Arc::clone(&deadline);
+    engine.on_progress(move |_operations| {
+      let deadline = progress_deadline.lock().map(|deadline| *deadline).ok()?;
+      (Instant::now() > deadline).then(|| "time limit exceeded".into())
+    });
+
+    let queue = |output: &Arc<Mutex<Vec<ScriptCommand>>>| {
+      let output = Arc::clone(output);
+      move |command: ScriptCommand| {
+        if let Ok(mut output) = output.lock() {
+          output.push(command);
+        }
+      }
+    };
+
+    let push = queue(&output);
+    engine.register_fn("move_by", move |dx: i64| {
+      push(ScriptCommand::MoveBy {
+        dx: dx.clamp(-4000, 4000) as i32,
+      });
+    });
+    let push = queue(&output);
+    engine.register_fn("say", move |text: &str| {
+      push(ScriptCommand::Say {
+        text: text.chars().take(SAY_LIMIT).collect(),
+      });
+    });
+    let push = queue(&output);
+    engine.register_fn("set_expression", move |expression: &str| {
+      if is_plain_name(expression) {
+        push(ScriptCommand::SetExpression {
+          expression: expression.into(),
+        });
+      }
+    });
+    let push = queue(&output);
+    engine.register_fn("play_voice", move |voice: &str| {
+      if is_plain_name(voice) {
+        push(ScriptCommand::PlayVoice { voice: voice.into() });
+      }
+    });
+    let push = queue(&output);
+    engine.register_fn("spawn_pumpkin", move || {
+      push(ScriptCommand::SpawnPumpkin { food: None })
+    });
+    let push = queue(&output);
+    engine.register_fn("spawn_pumpkin", move |food: &str| {
+      push(ScriptCommand::SpawnPumpkin {
+        food: Some(food.into()),
+      });
+    });
+
+    Self {
+      engine,
+      scripts: BTreeMap::new(),
+      deadline,
+      output,
+    }
+  }
+
+  /// Compiles new and changed scripts in `dir` and forgets deleted ones.
+  /// Scripts that fail to compile stay inactive until they are saved again.
+  pub fn reload(&mut self, dir: &Path) {
+    let mut present: Vec<(PathBuf, Option<SystemTime>)> = fs::read_dir(dir)
+      .map(|entries| {
+        entries
+          .filter_map(|entry| entry.ok())
+          .map(|entry| entry.path())
+          .filter(|path| path.extension().is_some_and(|extension| extension == "rhai"))
+          .map(|path| {
+            let modified = fs::metadata(&path).and_then(|metadata| metadata.modified()).ok();
+            (path, modified)
+          })
+          .collect()
+      })
+      .unwrap_or_default();
+    present.sort();
+
+    self
+      .scripts
+      .retain(|path, _| present.iter().any(|(present_path, _)| present_path == path));
+    for (path, modified) in present {
+      if self
+        .scripts
+        .get(&path)
+        .is_some_and(|loaded| loaded.modified == modified)
+      {
+        continue;
+      }
+
+      let compiled = fs::read_to_string(&path)
+        .map_err(|error| error.to_string())
+        .and_then(|source| self.engine.compile(source).map_err(|error| error.to_string()));
+      let ast = match compiled {
+        Ok(ast) => {
+          log::info!("loaded script {}", path.display());
+          Some(ast)
+        }
+        Err(error) => {
+          log::warn!("script {} failed to compile: {}", path.display(), error);
+          None
+        }
+      };
+      self.scripts.insert(path, LoadedScript { modified, ast });
+    }
+  }
+
+  /// Runs the event's handler in every script that defines it and returns
+  /// the commands they queued. Each call gets its own time budget.
+  pub fn dispatch(&mut self, settings: &ScriptingSettings, event: &ScriptEvent) -> Vec<ScriptCommand> {
+    self.engine.set_max_operations(settings.max_operations);
+    let handler = event.handler();
+    let args = event.args();
+
+    for (path, script) in &self.scripts {
+      let Some(ast) = &script.ast else {
+        continue;
+      };
+      let defined = ast
+        .iter_functions()
+        .any(|function| function.name == handler && function.params.len() == args.len());
+      if !defined {
+        continue;
+      }
+
+      if let Ok(mut deadline) = self.deadline.lock() {
+        *deadline = Instant::now() + Duration::from_millis(settings.time_limit_ms);
+      }
+      let options = CallFnOptions::new().eval_ast(false).rewind_scope(true);
+      let result =
+        self
+          .engine
+          .call_fn_with_options::<Dynamic>(options, &mut Scope::new(), ast, handler, args.clone());
+      if let Err(error) = result {
+        log::warn!("script {} {} failed: {}", path.display(), handler, error);
+      }
+    }
+
+    self
+      .output
+      .lock()
+      .map(|mut output| std::mem::take(&mut *output))
+      .unwrap_or_default()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn scripts_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("scripting-test-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+  }
+
+  fn write_script(path: &Path, source: &str, modified_secs: u64) {
+    fs::write(path, source).unwrap();
+    fs::File::options()
+      .write(true)
+      .open(path)
+      .and_then(|file| file.set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(modified_secs)))
+      .unwrap();
+  }
+
+  fn run(host: &mut ScriptHost, settings: &ScriptingSettings, event: &ScriptEvent) -> Vec<serde_json::Value> {
+    host
+      .dispatch(settings, event)
+      .iter()
+      .map(|command| serde_json::to_value(command).unwrap())
+      .collect()
+  }
+
+  #[test]
+  fn handlers_queue_sanitized_commands() {
+    let dir = scripts_dir("commands");
+    write_script(
+      &dir.join("pet.rhai"),
+      r#"
+        fn on_eaten(food, nutrition) { say(food + " " + nutrition); }
+        fn on_clicked() {
+          move_by(99999);
+          set_expression("../secret");
+          play_voice("hi.wav");
+          spawn_pumpkin("golden");
+        }
+      "#,
+      1,
+    );
+    let mut host = ScriptHost::new();
+    host.reload(&dir);
+    let settings = ScriptingSettings::default();
+
+    assert_eq!(
+      run(&mut host, &settings, &ScriptEvent::Clicked),
+      [
+        serde_json::json!({ "command": "move_by", "dx": 4000 }),
+        serde_json::json!({ "command": "play_voice", "voice": "hi.wav" }),
+        serde_json::json!({ "command": "spawn_pumpkin", "food": "golden" }),
+      ]
+    );
+    let eaten = ScriptEvent::Eaten {
+      food: "pumpkin".into(),
+      nutrition: 10,
+    };
+    assert_eq!(
+      run(&mut host, &settings, &eaten),
+      [serde_json::json!({ "command": "say", "text": "pumpkin 10" })]
+    );
+    assert!(run(&mut host, &settings, &ScriptEvent::Timeout).is_empty());
+    let _ = fs::remove_dir_all(dir);
+  }
+
+  #[test]
+  fn runaway_handlers_are_stopped_by_the_time_limit() {
+    let dir = scripts_dir("loop");
+    write_script(&dir.join("a.rhai"), r#"fn on_timeout() { say("before"); loop {} }"#, 1);
+    write_script(&dir.join("b.rhai"), r#"fn on_timeout() { say("after"); }"#, 1);
+    let mut host = ScriptHost::new();
+    host.reload(&dir);
+    let settings = ScriptingSettings {
+      max_operations: 0,
+      ..ScriptingSettings::default()
+    };
+
+    let started = Instant::now();
+    let commands = run(&mut host, &settings, &ScriptEvent::Timeout);
+    assert!(started.elapsed() < Duration::from_secs(2));
+    assert_eq!(
+      commands,
+      [
+        serde_json::json!({ "command": "say", "text": "before" }),
+        serde_json::json!({ "command": "say", "text": "after" }),
+      ]
+    );
+    let _ = fs::remove_dir_all(dir);
+  }
+
+  #[test]
+  fn reload_picks_up_edits_and_deletions() {
+    let dir = scripts_dir("reload");
+    let path = dir.join("hello.rhai");
+    let settings = ScriptingSettings::default();
+    let mut host = ScriptHost::new();
+    let said = |host: &mut ScriptHost| run(host, &settings, &ScriptEvent::Clicked);
+
+    write_script(&path, r#"fn on_clicked() { say("one"); }"#, 1);
+    host.reload(&dir);
+    assert_eq!(
+      said(&mut host),
+      [serde_json::json!({ "command": "say", "text": "one" })]
+    );
+
+    write_script(&path, r#"fn on_clicked() { say("two"); }"#, 2);
+    host.reload(&dir);
+    assert_eq!(
+      said(&mut host),
+      [serde_json::json!({ "command": "say", "text": "two" })]
+    );
+
+    write_script(&path, "fn on_clicked( {", 3);
+    host.reload(&dir);
+    assert!(said(&mut host).is_empty());
+
+    fs::remove_file(&path).unwrap();
+    host.reload(&dir);
+    assert!(host.scripts.is_empty());
+    let _ = fs::remove_dir_all(dir);
+  }
+}
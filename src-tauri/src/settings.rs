@@ -0,0 +1,108 @@
+use crate::{
+  audio::AudioSettings, bond::BondSettings, carry::CarrySettings, catch_game::CatchSettings,
+  character::CharacterSettings, cursor::CursorSettings, feeder::FeederSettings, flee::FleeSettings, gait::GaitSettings,
+  gesture::GestureSettings, hide_seek::HideSeekSettings, meal::EatingSettings, mood::MoodSettings, path_planner::Rect,
+  pumpkin_life::PumpkinLifeSettings, scripting::ScriptingSettings, sickness::SicknessSettings, sleep::SleepSettings,
+  throw::ThrowSettings,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+  fs,
+  path::{Path, PathBuf},
+  sync::{Mutex, OnceLock},
+};
+
+const SETTINGS_FILE_NAME: &str = "settings.json";
+
+static SETTINGS: OnceLock<Mutex<Settings>> = OnceLock::new();
+static SETTINGS_PATH: OnceLock<PathBuf> = OnceLock::new();
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WalkSettings {
+  pub avoid_cursor: bool,
+  pub cursor_keep_out_radius: i32,
+  pub avoid_other_windows: bool,
+  pub keep_out_zones: Vec<Rect>,
+  pub grid_cell_size: i32,
+}
+
+impl Default for WalkSettings {
+  fn default() -> Self {
+    Self {
+      avoid_cursor: true,
+      cursor_keep_out_radius: 60,
+      avoid_other_windows: true,
+      keep_out_zones: Vec::new(),
+      grid_cell_size: 16,
+    }
+  }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+  pub walk: WalkSettings,
+  pub gait: GaitSettings,
+  pub pumpkin_life: PumpkinLifeSettings,
+  pub feeder: FeederSettings,
+  pub eating: EatingSettings,
+  pub carry: CarrySettings,
+  pub flee: FleeSettings,
+  pub catch_game: CatchSettings,
+  pub hide_seek: HideSeekSettings,
+  pub mood: MoodSettings,
+  pub sleep: SleepSettings,
+  pub sickness: SicknessSettings,
+  pub bond: BondSettings,
+  pub gesture: GestureSettings,
+  pub throw: ThrowSettings,
+  pub cursor: CursorSettings,
+  pub scripting: ScriptingSettings,
+  pub character: CharacterSettings,
+  pub audio: AudioSettings,
+}
+
+fn settings_state() -> &'static Mutex<Settings> {
+  SETTINGS.get_or_init(|| Mutex::new(Settings::default()))
+}
+
+/// Reads `settings.json` from `config_dir`, falling back to defaults when the
+/// file is missing or unreadable. Later saves go to the same directory.
+pub fn load_settings(config_dir: &Path) {
+  let path = config_dir.join(SETTINGS_FILE_NAME);
+  let _ = SETTINGS_PATH.set(path.clone());
+
+  let mut loaded = fs::read_to_string(&path)
+    .ok()
+    .and_then(|raw| serde_json::from_str::<Settings>(&raw).ok())
+    .unwrap_or_default();
+  loaded.bond.sort_levels();
+
+  if let Ok(mut settings) = settings_state().lock() {
+    *settings = loaded;
+  }
+}
+
+pub fn current_settings() -> Settings {
+  settings_state()
+    .lock()
+    .map(|settings| settings.clone())
+    .unwrap_or_default()
+}
+
+pub fn replace_settings(mut next: Settings) -> Result<(), String> {
+  next.bond.sort_levels();
+  if let Ok(mut settings) = settings_state().lock() {
+    *settings = next.clone();
+  }
+
+  let Some(path) = SETTINGS_PATH.get() else {
+    return Ok(());
+  };
+  if let Some(parent) = path.parent() {
+    fs::create_dir_all(parent).map_err(|error| error.to_string())?;
+  }
+  let raw = serde_json::to_string_pretty(&next).map_err(|error| error.to_string())?;
+  fs::write(path, raw).map_err(|error| error.to_string())
+}
@@ -0,0 +1,160 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SicknessSettings {
+  pub enabled: bool,
+  /// How long the pumpkin index has to sit at 0 before the character falls sick.
+  pub sick_after_zero_secs: u64,
+  /// Whole meals (disliked food does not count) needed to recover.
+  pub recovery_meals: u32,
+  /// Multiplier on chase speed while sick.
+  pub speed_multiplier: f64,
+  pub expression: String,
+  pub voice: String,
+}
+
+impl Default for SicknessSettings {
+  fn default() -> Self {
+    Self {
+      enabled: true,
+      sick_after_zero_secs: 180,
+      recovery_meals: 3,
+      speed_multiplier: 0.5,
+      expression: "crying3".into(),
+      voice: "eww2.WAV".into(),
+    }
+  }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "transition", rename_all = "snake_case")]
+pub enum SicknessTransition {
+  FellSick { zero_secs: u64 },
+  Recovering { meals: u32, needed: u32 },
+  Recovered,
+}
+
+/// Persisted so that quitting while sick does not cure the character.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SicknessState {
+  pub sick: bool,
+  /// Time spent at index 0 while healthy.
+  pub zero_ms: u64,
+  /// Meals eaten since falling sick.
+  pub meals: u32,
+}
+
+impl SicknessState {
+  pub fn tick(
+    &mut self,
+    settings: &SicknessSettings,
+    pumpkin_index: u32,
+    elapsed_ms: u64,
+  ) -> Option<SicknessTransition> {
+    if self.sick || !settings.enabled {
+      return None;
+    }
+    if pumpkin_index > 0 {
+      self.zero_ms = 0;
+      return None;
+    }
+
+    self.zero_ms = self.zero_ms.saturating_add(elapsed_ms);
+    if self.zero_ms < settings.sick_after_zero_secs.saturating_mul(1000) {
+      return None;
+    }
+
+    let zero_secs = self.zero_ms / 1000;
+    *self = Self {
+      sick: true,
+      zero_ms: 0,
+      meals: 0,
+    };
+    Some(SicknessTransition::FellSick { zero_secs })
+  }
+
+  pub fn meal_finished(&mut self, settings: &SicknessSettings) -> Option<SicknessTransition> {
+    if !self.sick {
+      return None;
+    }
+
+    self.meals += 1;
+    let needed = settings.recovery_meals.max(1);
+    if self.meals < needed {
+      return Some(SicknessTransition::Recovering {
+        meals: self.meals,
+        needed,
+      });
+    }
+
+    *self = Self::default();
+    Some(SicknessTransition::Recovered)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const SECOND_MS: u64 = 1_000;
+
+  #[test]
+  fn falls_sick_after_the_index_sits_at_zero() {
+    let settings = SicknessSettings::default();
+    let mut state = SicknessState::default();
+
+    assert_eq!(state.tick(&settings, 0, 179 * SECOND_MS), None);
+    assert_eq!(
+      state.tick(&settings, 0, SECOND_MS),
+      Some(SicknessTransition::FellSick { zero_secs: 180 })
+    );
+    assert!(state.sick);
+    assert_eq!(state.tick(&settings, 0, 600 * SECOND_MS), None);
+  }
+
+  #[test]
+  fn any_food_resets_the_zero_timer() {
+    let settings = SicknessSettings::default();
+    let mut state = SicknessState::default();
+
+    state.tick(&settings, 0, 170 * SECOND_MS);
+    assert_eq!(state.tick(&settings, 1, SECOND_MS), None);
+    assert_eq!(state.zero_ms, 0);
+    assert_eq!(state.tick(&settings, 0, 170 * SECOND_MS), None);
+    assert!(!state.sick);
+  }
+
+  #[test]
+  fn recovers_after_enough_meals() {
+    let settings = SicknessSettings::default();
+    let mut state = SicknessState {
+      sick: true,
+      ..SicknessState::default()
+    };
+
+    assert_eq!(
+      state.meal_finished(&settings),
+      Some(SicknessTransition::Recovering { meals: 1, needed: 3 })
+    );
+    assert_eq!(
+      state.meal_finished(&settings),
+      Some(SicknessTransition::Recovering { meals: 2, needed: 3 })
+    );
+    assert_eq!(state.meal_finished(&settings), Some(SicknessTransition::Recovered));
+    assert!(!state.sick);
+    assert_eq!(state.meal_finished(&settings), None);
+  }
+
+  #[test]
+  fn disabled_sickness_never_starts() {
+    let settings = SicknessSettings {
+      enabled: false,
+      ..SicknessSettings::default()
+    };
+    let mut state = SicknessState::default();
+    assert_eq!(state.tick(&settings, 0, 3_600 * SECOND_MS), None);
+    assert_eq!(state.zero_ms, 0);
+  }
+}
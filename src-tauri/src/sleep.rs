@@ -0,0 +1,126 @@
+use crate::clock::QuietHours;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SleepSettings {
+  pub enabled: bool,
+  /// Dozes off after this long without a click, drag, pumpkin or game.
+  pub idle_after_secs: u64,
+  /// During these hours the character falls asleep much sooner.
+  pub night_hours: Option<QuietHours>,
+  pub night_idle_after_secs: u64,
+  /// Scales every draining need while asleep.
+  pub decay_multiplier: f64,
+  /// Energy recovered per minute of sleep, on top of the normal drift.
+  pub energy_per_minute: f64,
+  /// Expression id shown while asleep.
+  pub expression: String,
+}
+
+impl Default for SleepSettings {
+  fn default() -> Self {
+    Self {
+      enabled: true,
+      idle_after_secs: 600,
+      night_hours: Some(QuietHours {
+        start: "23:00".into(),
+        end: "07:00".into(),
+      }),
+      night_idle_after_secs: 60,
+      decay_multiplier: 0.3,
+      energy_per_minute: 4.0,
+      expression: "smile".into(),
+    }
+  }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SleepReason {
+  Idle,
+  Night,
+  /// A nap the behavior tree decided on.
+  Nap,
+}
+
+impl SleepSettings {
+  pub fn is_night(&self, minute_of_day: u32) -> bool {
+    self
+      .night_hours
+      .as_ref()
+      .is_some_and(|night| night.contains(minute_of_day))
+  }
+
+  /// Whether the character should be asleep after `idle_ms` without any
+  /// interaction. Waking it resets the idle time, so a character woken at
+  /// night stays up for `night_idle_after_secs` before dozing off again.
+  pub fn should_sleep(&self, idle_ms: u64, minute_of_day: u32) -> Option<SleepReason> {
+    if !self.enabled {
+      return None;
+    }
+    if self.is_night(minute_of_day) && idle_ms >= self.night_idle_after_secs.saturating_mul(1000) {
+      return Some(SleepReason::Night);
+    }
+    (idle_ms >= self.idle_after_secs.saturating_mul(1000)).then_some(SleepReason::Idle)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::clock::minute_of_day;
+  use chrono::{NaiveDate, NaiveDateTime};
+
+  const MINUTE_MS: u64 = 60_000;
+
+  fn at(day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2026, 3, day)
+      .and_then(|date| date.and_hms_opt(hour, minute, 0))
+      .unwrap()
+  }
+
+  #[test]
+  fn dozes_off_sooner_across_the_night_window() {
+    let settings = SleepSettings::default();
+    let sleep_after_two_idle_minutes = |now: NaiveDateTime| settings.should_sleep(2 * MINUTE_MS, minute_of_day(now));
+
+    assert_eq!(sleep_after_two_idle_minutes(at(1, 22, 59)), None);
+    assert_eq!(sleep_after_two_idle_minutes(at(1, 23, 0)), Some(SleepReason::Night));
+    assert_eq!(sleep_after_two_idle_minutes(at(2, 0, 0)), Some(SleepReason::Night));
+    assert_eq!(sleep_after_two_idle_minutes(at(2, 6, 59)), Some(SleepReason::Night));
+    assert_eq!(sleep_after_two_idle_minutes(at(2, 7, 0)), None);
+  }
+
+  #[test]
+  fn a_woken_character_stays_up_for_the_night_delay() {
+    let settings = SleepSettings::default();
+    let night = minute_of_day(at(2, 2, 0));
+    assert_eq!(settings.should_sleep(59_999, night), None);
+    assert_eq!(settings.should_sleep(60_000, night), Some(SleepReason::Night));
+  }
+
+  #[test]
+  fn sleeps_after_the_day_idle_limit() {
+    let settings = SleepSettings::default();
+    let noon = minute_of_day(at(1, 12, 0));
+    assert_eq!(settings.should_sleep(10 * MINUTE_MS - 1, noon), None);
+    assert_eq!(settings.should_sleep(10 * MINUTE_MS, noon), Some(SleepReason::Idle));
+  }
+
+  #[test]
+  fn disabled_or_without_night_hours() {
+    let disabled = SleepSettings {
+      enabled: false,
+      ..SleepSettings::default()
+    };
+    assert_eq!(disabled.should_sleep(60 * MINUTE_MS, 0), None);
+
+    let no_night = SleepSettings {
+      night_hours: None,
+      ..SleepSettings::default()
+    };
+    assert!(!no_night.is_night(0));
+    assert_eq!(no_night.should_sleep(2 * MINUTE_MS, 0), None);
+  }
+}
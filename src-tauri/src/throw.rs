@@ -0,0 +1,292 @@
+use crate::path_planner::Rect;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThrowSettings {
+  pub enabled: bool,
+  /// Release speeds below this (pixels per second) just drop the character.
+  pub min_speed: f64,
+  pub max_speed: f64,
+  /// How far back release velocity is measured.
+  pub velocity_window_ms: u64,
+  /// Exponential slow-down per second while sliding.
+  pub friction: f64,
+  /// Share of the speed kept after bouncing off an edge.
+  pub restitution: f64,
+  /// Impacts at least this fast make the character complain.
+  pub hard_impact_speed: f64,
+  /// With ground mode the character falls to the bottom of the screen and
+  /// rests there, instead of sliding across the desktop.
+  pub ground_mode: bool,
+  /// Pixels per second squared, ground mode only.
+  pub gravity: f64,
+}
+
+impl Default for ThrowSettings {
+  fn default() -> Self {
+    Self {
+      enabled: true,
+      min_speed: 350.0,
+      max_speed: 4000.0,
+      velocity_window_ms: 90,
+      friction: 2.6,
+      restitution: 0.5,
+      hard_impact_speed: 1500.0,
+      ground_mode: false,
+      gravity: 2600.0,
+    }
+  }
+}
+
+/// Recent window positions during a drag.
+#[derive(Debug, Default)]
+pub struct VelocityTracker {
+  samples: VecDeque<(f64, f64, u64)>,
+}
+
+impl VelocityTracker {
+  const MAX_SAMPLES: usize = 32;
+
+  pub fn clear(&mut self) {
+    self.samples.clear();
+  }
+
+  pub fn push(&mut self, x: i32, y: i32, t_ms: u64) {
+    self.samples.push_back((x as f64, y as f64, t_ms));
+    if self.samples.len() > Self::MAX_SAMPLES {
+      self.samples.pop_front();
+    }
+  }
+
+  pub fn last_sample_ms(&self) -> Option<u64> {
+    self.samples.back().map(|&(_, _, t)| t)
+  }
+
+  /// Pixels per second over the last `window_ms` before `now_ms`. A drag
+  /// that stopped before release has no velocity left.
+  pub fn velocity(&self, window_ms: u64, now_ms: u64) -> (f64, f64) {
+    let Some(&(last_x, last_y, last_t)) = self.samples.back() else {
+      return (0.0, 0.0);
+    };
+    if now_ms.saturating_sub(last_t) > window_ms {
+      return (0.0, 0.0);
+    }
+    let Some(&(first_x, first_y, first_t)) = self
+      .samples
+      .iter()
+      .find(|(_, _, t)| last_t.saturating_sub(*t) <= window_ms)
+    else {
+      return (0.0, 0.0);
+    };
+    let dt = last_t.saturating_sub(first_t);
+    if dt == 0 {
+      return (0.0, 0.0);
+    }
+    let seconds = dt as f64 / 1000.0;
+    ((last_x - first_x) / seconds, (last_y - first_y) / seconds)
+  }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Edge {
+  Left,
+  Right,
+  Top,
+  Bottom,
+}
+
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ThrowEvent {
+  Launched,
+  Impact { edge: Edge, speed: f64, hard: bool },
+  Settled { landed: bool },
+}
+
+/// The character window in flight.
+#[derive(Clone, Copy, Debug)]
+pub struct ThrowBody {
+  pub x: f64,
+  pub y: f64,
+  vx: f64,
+  vy: f64,
+}
+
+const REST_SPEED: f64 = 25.0;
+
+impl ThrowBody {
+  pub fn launch(settings: &ThrowSettings, x: i32, y: i32, velocity: (f64, f64)) -> Self {
+    let (mut vx, mut vy) = velocity;
+    let speed = vx.hypot(vy);
+    if speed > settings.max_speed {
+      vx *= settings.max_speed / speed;
+      vy *= settings.max_speed / speed;
+    }
+    Self {
+      x: x as f64,
+      y: y as f64,
+      vx,
+      vy,
+    }
+  }
+
+  fn on_floor(&self, bounds: Rect, height: i32) -> bool {
+    self.y >= (bounds.bottom() - height) as f64 - 0.5
+  }
+
+  /// Advances the flight by `dt_ms`. Returns the impacts that happened and,
+  /// once the body has come to rest, a `Settled` event.
+  pub fn step(
+    &mut self,
+    settings: &ThrowSettings,
+    dt_ms: u64,
+    bounds: Rect,
+    width: i32,
+    height: i32,
+  ) -> Vec<ThrowEvent> {
+    let mut events = Vec::new();
+    let dt = dt_ms as f64 / 1000.0;
+
+    if settings.ground_mode {
+      self.vy += settings.gravity * dt;
+      if self.on_floor(bounds, height) {
+        self.vx *= (-settings.friction * 2.0 * dt).exp();
+      }
+    } else {
+      let damping = (-settings.friction * dt).exp();
+      self.vx *= damping;
+      self.vy *= damping;
+    }
+
+    self.x += self.vx * dt;
+    self.y += self.vy * dt;
+
+    let min_x = bounds.x as f64;
+    let max_x = (bounds.right() - width).max(bounds.x) as f64;
+    let min_y = bounds.y as f64;
+    let max_y = (bounds.bottom() - height).max(bounds.y) as f64;
+    let mut bounce = |position: &mut f64, velocity: &mut f64, min: f64, max: f64, edges: (Edge, Edge)| {
+      let (limit, edge) = if *position < min {
+        (min, edges.0)
+      } else if *position > max {
+        (max, edges.1)
+      } else {
+        return;
+      };
+      *position = limit;
+      let speed = velocity.abs();
+      *velocity = -*velocity * settings.restitution;
+      if speed >= REST_SPEED * 4.0 {
+        events.push(ThrowEvent::Impact {
+          edge,
+          speed,
+          hard: speed >= settings.hard_impact_speed,
+        });
+      }
+    };
+    bounce(&mut self.x, &mut self.vx, min_x, max_x, (Edge::Left, Edge::Right));
+    bounce(&mut self.y, &mut self.vy, min_y, max_y, (Edge::Top, Edge::Bottom));
+
+    let resting = if settings.ground_mode {
+      let on_floor = self.on_floor(bounds, height);
+      if on_floor && self.vy.abs() < REST_SPEED * 4.0 {
+        self.vy = 0.0;
+      }
+      on_floor && self.vy == 0.0 && self.vx.abs() < REST_SPEED
+    } else {
+      self.vx.hypot(self.vy) < REST_SPEED
+    };
+    if resting {
+      events.push(ThrowEvent::Settled {
+        landed: settings.ground_mode,
+      });
+    }
+    events
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn screen() -> Rect {
+    Rect::new(0, 0, 1920, 1080)
+  }
+
+  /// Steps the body in 16ms frames until it settles, returning every event.
+  fn fly(settings: &ThrowSettings, body: &mut ThrowBody) -> Vec<ThrowEvent> {
+    let mut events = Vec::new();
+    for _ in 0..1_000 {
+      events.extend(body.step(settings, 16, screen(), 100, 100));
+      if matches!(events.last(), Some(ThrowEvent::Settled { .. })) {
+        return events;
+      }
+    }
+    panic!("the body never settled");
+  }
+
+  #[test]
+  fn velocity_uses_only_the_recent_window() {
+    let mut tracker = VelocityTracker::default();
+    assert_eq!(tracker.velocity(90, 0), (0.0, 0.0));
+
+    tracker.push(0, 0, 0);
+    tracker.push(10, 0, 50);
+    tracker.push(40, -20, 100);
+    assert_eq!(tracker.velocity(90, 100), (600.0, -400.0));
+    assert_eq!(tracker.velocity(90, 300), (0.0, 0.0));
+  }
+
+  #[test]
+  fn launch_caps_the_speed() {
+    let body = ThrowBody::launch(&ThrowSettings::default(), 0, 0, (6_000.0, 8_000.0));
+    assert_eq!((body.vx, body.vy), (2_400.0, 3_200.0));
+  }
+
+  #[test]
+  fn edges_bounce_and_report_hard_impacts() {
+    let settings = ThrowSettings::default();
+    let mut body = ThrowBody::launch(&settings, 1_800, 500, (2_000.0, 0.0));
+
+    let events = body.step(&settings, 16, screen(), 100, 100);
+    assert_eq!(body.x, 1_820.0);
+    assert!(body.vx < 0.0);
+    assert!(matches!(
+      events.as_slice(),
+      [ThrowEvent::Impact {
+        edge: Edge::Right,
+        hard: true,
+        ..
+      }]
+    ));
+  }
+
+  #[test]
+  fn sliding_slows_to_a_stop_on_screen() {
+    let settings = ThrowSettings::default();
+    let mut body = ThrowBody::launch(&settings, 900, 500, (400.0, 0.0));
+
+    let events = fly(&settings, &mut body);
+    assert!(matches!(events.as_slice(), [ThrowEvent::Settled { landed: false }]));
+    assert!(body.x > 900.0 && body.x < 1_820.0);
+  }
+
+  #[test]
+  fn ground_mode_falls_and_lands_on_the_floor() {
+    let settings = ThrowSettings {
+      ground_mode: true,
+      ..ThrowSettings::default()
+    };
+    let mut body = ThrowBody::launch(&settings, 900, 0, (0.0, 0.0));
+
+    let events = fly(&settings, &mut body);
+    assert!(events
+      .iter()
+      .any(|event| matches!(event, ThrowEvent::Impact { edge: Edge::Bottom, .. })));
+    assert!(matches!(events.last(), Some(ThrowEvent::Settled { landed: true })));
+    assert_eq!(body.y, 980.0);
+  }
+}
@@ -0,0 +1,279 @@
+use crate::{mood::Mood, rng::Dice};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+/// How one clip is picked, wherever it appears.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClipTuning {
+  /// Relative odds against the other clips in a pool; 0 never plays it.
+  pub weight: u32,
+  /// The clip is skipped for this long after it played.
+  pub cooldown_ms: u64,
+  /// Only picked in these moods; empty allows every mood.
+  pub moods: Vec<Mood>,
+}
+
+impl Default for ClipTuning {
+  fn default() -> Self {
+    Self {
+      weight: 1,
+      cooldown_ms: 0,
+      moods: Vec::new(),
+    }
+  }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VoiceSelection {
+  /// How many recent clips are avoided while something else is available.
+  pub history: usize,
+  /// Keyed by file name under `voice/`.
+  pub clips: BTreeMap<String, ClipTuning>,
+}
+
+impl Default for VoiceSelection {
+  fn default() -> Self {
+    Self {
+      history: 2,
+      clips: BTreeMap::new(),
+    }
+  }
+}
+
+impl VoiceSelection {
+  pub fn validate(&self) -> Result<(), String> {
+    if self.history > 16 {
+      return Err(format!("voice_selection.history {} is above 16", self.history));
+    }
+    Ok(())
+  }
+}
+
+/// Remembers what played recently so picks do not repeat and cooldowns hold.
+#[derive(Debug, Default)]
+pub struct VoiceSelector {
+  history: VecDeque<String>,
+  last_played_ms: HashMap<String, u64>,
+}
+
+impl VoiceSelector {
+  /// Picks a clip from `pool` by weight among those allowed in `mood` and off
+  /// cooldown, preferring ones outside the recent history. `None` when every
+  /// clip is filtered out or cooling down.
+  pub fn pick(
+    &mut self,
+    selection: &VoiceSelection,
+    pool: &[String],
+    mood: Mood,
+    now_ms: u64,
+    dice: &mut Dice,
+  ) -> Option<String> {
+    let ready: Vec<(&String, u32)> = pool
+      .iter()
+      .filter_map(|voice| {
+        let tuning = selection.clips.get(voice).cloned().unwrap_or_default();
+        let allowed = tuning.weight > 0 && (tuning.moods.is_empty() || tuning.moods.contains(&mood));
+        let cooling = self
+          .last_played_ms
+          .get(voice)
+          .is_some_and(|played| now_ms < played.saturating_add(tuning.cooldown_ms));
+        (allowed && !cooling).then_some((voice, tuning.weight))
+      })
+      .collect();
+    let fresh: Vec<(&String, u32)> = ready
+      .iter()
+      .copied()
+      .filter(|(voice, _)| !self.history.contains(voice))
+      .collect();
+    let candidates = if fresh.is_empty() { ready } else { fresh };
+
+    let total: u64 = candidates.iter().map(|(_, weight)| *weight as u64).sum();
+    if total == 0 {
+      return None;
+    }
+    let mut roll = dice.next_u64() % total;
+    let picked = candidates.iter().find_map(|(voice, weight)| {
+      if roll < *weight as u64 {
+        return Some((*voice).clone());
+      }
+      roll -= *weight as u64;
+      None
+    })?;
+
+    self.history.push_back(picked.clone());
+    while self.history.len() > selection.history {
+      self.history.pop_front();
+    }
+    self.last_played_ms.insert(picked.clone(), now_ms);
+    Some(picked)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const PICKS: usize = 200;
+
+  fn pool(names: &[&str]) -> Vec<String> {
+    names.iter().map(|name| name.to_string()).collect()
+  }
+
+  fn selection(history: usize, clips: &[(&str, ClipTuning)]) -> VoiceSelection {
+    VoiceSelection {
+      history,
+      clips: clips
+        .iter()
+        .map(|(name, tuning)| (name.to_string(), tuning.clone()))
+        .collect(),
+    }
+  }
+
+  #[test]
+  fn weight_zero_never_plays() {
+    let selection = selection(
+      0,
+      &[(
+        "muted.wav",
+        ClipTuning {
+          weight: 0,
+          ..ClipTuning::default()
+        },
+      )],
+    );
+    let pool = pool(&["muted.wav", "a.wav", "b.wav"]);
+    let mut selector = VoiceSelector::default();
+    let mut dice = Dice::new(7);
+
+    for now_ms in 0..PICKS as u64 {
+      let picked = selector
+        .pick(&selection, &pool, Mood::Happy, now_ms, &mut dice)
+        .unwrap();
+      assert_ne!(picked, "muted.wav");
+    }
+  }
+
+  #[test]
+  fn clips_are_skipped_while_cooling_down() {
+    let selection = selection(
+      0,
+      &[(
+        "only.wav",
+        ClipTuning {
+          cooldown_ms: 1_000,
+          ..ClipTuning::default()
+        },
+      )],
+    );
+    let pool = pool(&["only.wav"]);
+    let mut selector = VoiceSelector::default();
+    let mut dice = Dice::new(7);
+
+    assert_eq!(
+      selector
+        .pick(&selection, &pool, Mood::Happy, 5_000, &mut dice)
+        .as_deref(),
+      Some("only.wav")
+    );
+    assert_eq!(selector.pick(&selection, &pool, Mood::Happy, 5_999, &mut dice), None);
+    assert_eq!(
+      selector
+        .pick(&selection, &pool, Mood::Happy, 6_000, &mut dice)
+        .as_deref(),
+      Some("only.wav")
+    );
+  }
+
+  #[test]
+  fn mood_filter_limits_the_pool() {
+    let selection = selection(
+      0,
+      &[(
+        "hungry.wav",
+        ClipTuning {
+          moods: vec![Mood::Hungry, Mood::Starving],
+          ..ClipTuning::default()
+        },
+      )],
+    );
+    let pool = pool(&["hungry.wav", "any.wav"]);
+    let mut selector = VoiceSelector::default();
+    let mut dice = Dice::new(7);
+
+    for now_ms in 0..PICKS as u64 {
+      let picked = selector.pick(&selection, &pool, Mood::Happy, now_ms, &mut dice);
+      assert_eq!(picked.as_deref(), Some("any.wav"));
+    }
+    let hungry_picks = (0..PICKS as u64)
+      .filter_map(|now_ms| selector.pick(&selection, &pool, Mood::Hungry, now_ms, &mut dice))
+      .filter(|picked| picked == "hungry.wav")
+      .count();
+    assert!(hungry_picks > 0);
+  }
+
+  #[test]
+  fn recent_clips_are_avoided_while_fresh_ones_remain() {
+    let selection = selection(2, &[]);
+    let pool = pool(&["a.wav", "b.wav", "c.wav"]);
+    let mut selector = VoiceSelector::default();
+    let mut dice = Dice::new(7);
+
+    let mut picks = Vec::new();
+    for now_ms in 0..PICKS as u64 {
+      picks.push(
+        selector
+          .pick(&selection, &pool, Mood::Happy, now_ms, &mut dice)
+          .unwrap(),
+      );
+    }
+    for window in picks.windows(3) {
+      assert_ne!(window[0], window[1]);
+      assert_ne!(window[0], window[2]);
+      assert_ne!(window[1], window[2]);
+    }
+  }
+
+  #[test]
+  fn history_gives_way_when_nothing_fresh_is_left() {
+    let selection = selection(2, &[]);
+    let pool = pool(&["only.wav"]);
+    let mut selector = VoiceSelector::default();
+    let mut dice = Dice::new(7);
+
+    for now_ms in 0..3 {
+      let picked = selector.pick(&selection, &pool, Mood::Happy, now_ms, &mut dice);
+      assert_eq!(picked.as_deref(), Some("only.wav"));
+    }
+  }
+
+  #[test]
+  fn none_when_everything_is_filtered_out() {
+    let selection = selection(
+      2,
+      &[
+        (
+          "silent.wav",
+          ClipTuning {
+            weight: 0,
+            ..ClipTuning::default()
+          },
+        ),
+        (
+          "sad.wav",
+          ClipTuning {
+            moods: vec![Mood::Lonely],
+            ..ClipTuning::default()
+          },
+        ),
+      ],
+    );
+    let mut selector = VoiceSelector::default();
+    let mut dice = Dice::new(7);
+
+    let pool = pool(&["silent.wav", "sad.wav"]);
+    assert_eq!(selector.pick(&selection, &pool, Mood::Happy, 0, &mut dice), None);
+    assert_eq!(selector.pick(&selection, &[], Mood::Happy, 0, &mut dice), None);
+  }
+}
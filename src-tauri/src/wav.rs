@@ -0,0 +1,138 @@
+//! Just enough RIFF/WAVE parsing to validate voice clips. Uses nothing but
+//! `std` so `build.rs` can include it too.
+
+#[derive(Clone, Copy, Debug)]
+pub struct WavInfo {
+  pub channels: u16,
+  pub sample_rate: u32,
+  pub bits_per_sample: u16,
+  pub duration_secs: f64,
+}
+
+fn le_u16(bytes: &[u8], at: usize) -> u16 {
+  u16::from_le_bytes([bytes[at], bytes[at + 1]])
+}
+
+fn le_u32(bytes: &[u8], at: usize) -> u32 {
+  u32::from_le_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]])
+}
+
+/// Walks the RIFF chunks of a WAV file far enough to read its format and length.
+pub fn parse_wav(bytes: &[u8]) -> Result<WavInfo, String> {
+  if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+    return Err("not a RIFF/WAVE file".into());
+  }
+
+  let mut format = None;
+  let mut data_len = None;
+  let mut at = 12;
+  while at + 8 <= bytes.len() {
+    let id = &bytes[at..at + 4];
+    let len = le_u32(bytes, at + 4) as usize;
+    let body = at + 8;
+    match id {
+      b"fmt " if len >= 16 && body + 16 <= bytes.len() => {
+        let tag = le_u16(bytes, body);
+        if !matches!(tag, 1 | 3 | 0xFFFE) {
+          return Err(format!("unsupported WAV encoding {tag}"));
+        }
+        format = Some((
+          le_u16(bytes, body + 2),
+          le_u32(bytes, body + 4),
+          le_u16(bytes, body + 14),
+        ));
+      }
+      // Some encoders write a bogus length for the last chunk; trust the file.
+      b"data" => data_len = Some(len.min(bytes.len() - body)),
+      _ => {}
+    }
+    at = body.saturating_add(len).saturating_add(len % 2);
+  }
+
+  let (channels, sample_rate, bits_per_sample) = format.ok_or("missing fmt chunk")?;
+  let data_len = data_len.ok_or("missing data chunk")?;
+  if !(1..=2).contains(&channels) {
+    return Err(format!("{channels} channels; expected mono or stereo"));
+  }
+  if !(8_000..=96_000).contains(&sample_rate) {
+    return Err(format!("sample rate {sample_rate} Hz is outside 8000..=96000"));
+  }
+  if !matches!(bits_per_sample, 8 | 16 | 24 | 32) {
+    return Err(format!("{bits_per_sample}-bit samples are not supported"));
+  }
+  let bytes_per_second = sample_rate as f64 * channels as f64 * (bits_per_sample / 8) as f64;
+  Ok(WavInfo {
+    channels,
+    sample_rate,
+    bits_per_sample,
+    duration_secs: data_len as f64 / bytes_per_second,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// A RIFF file with a `fmt ` chunk, the `extra` chunks and `data_len` bytes of silence.
+  fn wav(channels: u16, sample_rate: u32, bits: u16, extra: &[(&[u8; 4], &[u8])], data_len: u32) -> Vec<u8> {
+    let mut body = b"WAVE".to_vec();
+    let block_align = channels * bits / 8;
+    body.extend(b"fmt ");
+    body.extend(16u32.to_le_bytes());
+    body.extend(1u16.to_le_bytes());
+    body.extend(channels.to_le_bytes());
+    body.extend(sample_rate.to_le_bytes());
+    body.extend((sample_rate * block_align as u32).to_le_bytes());
+    body.extend(block_align.to_le_bytes());
+    body.extend(bits.to_le_bytes());
+    for (id, data) in extra {
+      body.extend(*id);
+      body.extend((data.len() as u32).to_le_bytes());
+      body.extend(*data);
+      if data.len() % 2 == 1 {
+        body.push(0);
+      }
+    }
+    body.extend(b"data");
+    body.extend(data_len.to_le_bytes());
+    body.extend(vec![0; data_len as usize]);
+
+    let mut file = b"RIFF".to_vec();
+    file.extend((body.len() as u32).to_le_bytes());
+    file.extend(body);
+    file
+  }
+
+  #[test]
+  fn reads_format_and_length() {
+    let info = parse_wav(&wav(1, 44_100, 16, &[], 44_100)).unwrap();
+    assert_eq!((info.channels, info.sample_rate, info.bits_per_sample), (1, 44_100, 16));
+    assert_eq!(info.duration_secs, 0.5);
+  }
+
+  #[test]
+  fn skips_padded_chunks_before_the_data() {
+    let info = parse_wav(&wav(2, 8_000, 8, &[(b"LIST", b"odd")], 16_000)).unwrap();
+    assert_eq!(info.duration_secs, 1.0);
+  }
+
+  #[test]
+  fn trusts_the_file_over_a_bogus_data_length() {
+    let mut bytes = wav(1, 8_000, 16, &[], 1_600);
+    let data_len_at = bytes.len() - 1_600 - 4;
+    bytes[data_len_at..data_len_at + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert_eq!(parse_wav(&bytes).unwrap().duration_secs, 0.1);
+  }
+
+  #[test]
+  fn rejects_what_the_mixer_cannot_play() {
+    assert!(parse_wav(b"RIFF\0\0\0\0AVI ").is_err());
+    assert!(parse_wav(&wav(3, 44_100, 16, &[], 100)).is_err());
+    assert!(parse_wav(&wav(1, 4_000, 16, &[], 100)).is_err());
+    assert!(parse_wav(&wav(1, 44_100, 12, &[], 100)).is_err());
+
+    let mut no_data = wav(1, 44_100, 16, &[], 0);
+    no_data.truncate(no_data.len() - 8);
+    assert_eq!(parse_wav(&no_data).unwrap_err(), "missing data chunk");
+  }
+}